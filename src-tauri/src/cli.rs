@@ -0,0 +1,382 @@
+use crate::save_editor::SaveEdit;
+use crate::save_manager::SaveManager;
+use crate::save_validate::Finding;
+use crate::sync_config::DEFAULT_PROFILE;
+use crate::sync_log::{SyncLogEntry, SyncLogFilter};
+use crate::sync_plan::SyncPlan;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use std::path::Path;
+
+const COMMANDS: [&str; 8] = ["edit", "validate", "repair", "export", "import", "sync", "history", "help"];
+
+const USAGE: &str = "Usage: hollow-sync <command> [options]
+
+Commands:
+  edit <save> [--output <path>] [edits...]
+      writes <save>.edited.dat (or .json) next to the input unless --output is given
+      --geo <n>                  set geo (rosaries in Silksong)
+      --masks <n>                set max masks and refill health
+      --soul-vessels <n>         set soul vessels (0-3)
+      --charm <id>=<on|off>      give or take a charm (repeatable)
+      --tool <name>=<on|off>     give or take a Silksong tool (repeatable)
+      --bench <scene>:<marker>   set the respawn bench
+      --steel-soul <on|off>      toggle Steel Soul mode
+  validate <save>
+      decode a save and report structural problems
+  repair <save> [--output <path>]
+      fix truncated JSON, bad padding and a missing trailer, plus safe value fixes;
+      writes <save>.repaired.dat next to the input unless --output is given, and only if something was fixed
+  export <save> [--output <path>] [--minify]
+      write a PC or Switch save as JSON (pretty-printed unless --minify)
+  import <json> --to <pc|switch> [--output <path>]
+      check hand-edited JSON and encode it for PC or as a Switch save
+  sync <game> [--profile <name>] [--to <pc|switch>] [--force] [--dry-run]
+      sync a configured game (hk or silksong); --dry-run prints the plan and writes nothing
+  history [filters] [--json]
+      list past syncs, undos, conversions, backups and restores, newest first
+      --game <id>  --profile <name>  --operation <sync|undo|convert|backup|restore>
+      --failed     --since <date>    --until <date>   --path <text>   --limit <n>
+  help
+      show this message
+
+Running without a command starts the app.";
+
+// parsed as positionals plus `--name value` / `--flag` options, in order
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Self {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut iter = args.iter().peekable();
+
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                if let Some((name, value)) = name.split_once('=') {
+                    options.push((name.to_string(), Some(value.to_string())));
+                } else {
+                    let value = iter.next_if(|next| !next.starts_with("--")).cloned();
+                    options.push((name.to_string(), value));
+                }
+            } else {
+                positional.push(arg.clone());
+            }
+        }
+
+        Self { positional, options }
+    }
+
+    fn value(&self, name: &str) -> Option<&str> {
+        self.options.iter().rev().find(|(n, _)| n == name).and_then(|(_, v)| v.as_deref())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+}
+
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    args.get(1).map_or(false, |command| COMMANDS.contains(&command.as_str()) || command == "--help")
+}
+
+pub fn run(args: &[String]) -> i32 {
+    let command = args.get(1).map(|s| s.as_str()).unwrap_or("help");
+    let parsed = Args::parse(args.get(2..).unwrap_or(&[]));
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return 1;
+        }
+    };
+
+    let result = runtime.block_on(async {
+        match command {
+            "edit" => run_edit(&parsed).await,
+            "validate" => run_validate(&parsed).await,
+            "repair" => run_repair(&parsed).await,
+            "export" => run_export(&parsed).await,
+            "import" => run_import(&parsed).await,
+            "sync" => run_sync(&parsed).await,
+            "history" => run_history(&parsed).await,
+            _ => {
+                println!("{}", USAGE);
+                Ok(())
+            }
+        }
+    });
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+fn parse_switch(value: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    match value.to_lowercase().as_str() {
+        "on" | "true" | "yes" | "1" => Ok(true),
+        "off" | "false" | "no" | "0" => Ok(false),
+        _ => Err(format!("Expected on or off, got {}", value).into()),
+    }
+}
+
+fn parse_number(name: &str, value: Option<&str>) -> Result<i64, Box<dyn std::error::Error>> {
+    value
+        .ok_or_else(|| format!("--{} needs a value", name))?
+        .parse::<i64>()
+        .map_err(|_| format!("--{} needs a number", name).into())
+}
+
+fn parse_edits(args: &Args) -> Result<Vec<SaveEdit>, Box<dyn std::error::Error>> {
+    let mut edits = Vec::new();
+
+    for (name, value) in &args.options {
+        let edit = match name.as_str() {
+            "geo" => SaveEdit::Geo { value: parse_number(name, value.as_deref())? },
+            "masks" => SaveEdit::Masks { value: parse_number(name, value.as_deref())? },
+            "soul-vessels" => SaveEdit::SoulVessels { value: parse_number(name, value.as_deref())? },
+            "charm" | "tool" => {
+                let (id, state) = value
+                    .as_deref()
+                    .and_then(|v| v.rsplit_once('='))
+                    .ok_or_else(|| format!("--{} expects <id>=<on|off>", name))?;
+                let owned = parse_switch(state)?;
+                if name == "charm" {
+                    let charm = id.parse::<u32>().map_err(|_| format!("Invalid charm id: {}", id))?;
+                    SaveEdit::CharmOwned { charm, owned }
+                } else {
+                    SaveEdit::ToolOwned { tool: id.to_string(), owned }
+                }
+            }
+            "bench" => {
+                let (scene, marker) = value
+                    .as_deref()
+                    .and_then(|v| v.split_once(':'))
+                    .ok_or("--bench expects <scene>:<marker>")?;
+                SaveEdit::RespawnBench { scene: scene.to_string(), marker: marker.to_string() }
+            }
+            "steel-soul" => SaveEdit::SteelSoul {
+                enabled: parse_switch(value.as_deref().ok_or("--steel-soul needs on or off")?)?,
+            },
+            "output" => continue,
+            other => return Err(format!("Unknown option --{}", other).into()),
+        };
+        edits.push(edit);
+    }
+
+    Ok(edits)
+}
+
+async fn run_edit(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let input = args.positional.first().ok_or("edit needs a save file")?;
+    let edits = parse_edits(args)?;
+    if edits.is_empty() {
+        return Err("No edits given, see hollow-sync help".into());
+    }
+
+    let manager = SaveManager::new();
+    let output = &output_for(&manager, args, input, "edited")?;
+    let findings = manager.edit_save(input, output, &edits).await?;
+    print_findings(&findings);
+    println!("Applied {} edit(s), wrote {}", edits.len(), output);
+    Ok(())
+}
+
+// never the input itself unless asked for: a new file next to it, and zips or folders need --output
+fn output_for(manager: &SaveManager, args: &Args, input: &str, tag: &str) -> Result<String, Box<dyn std::error::Error>> {
+    match args.value("output") {
+        Some(output) => Ok(output.to_string()),
+        None if manager.is_backup_container(input) => Err(format!("{} is a backup zip or folder, pass --output for the {} save", input, tag).into()),
+        None => Ok(manager.sibling_output(input, tag)),
+    }
+}
+
+fn print_findings(findings: &[Finding]) {
+    for finding in findings {
+        match &finding.field {
+            Some(field) => println!("{}: {} ({})", finding.severity, finding.message, field),
+            None => println!("{}: {}", finding.severity, finding.message),
+        }
+    }
+}
+
+async fn run_validate(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let path = args.positional.first().ok_or("validate needs a save file")?;
+
+    let manager = SaveManager::new();
+    let report = manager.validate_save(path).await?;
+    print_findings(&report.findings);
+    if !report.is_valid() {
+        let fixable = report.findings.iter().filter(|finding| finding.fixable).count();
+        return Err(format!("Save is not valid, {} problem(s) can be fixed with hollow-sync repair", fixable).into());
+    }
+    println!("Save is valid ({} finding(s))", report.findings.len());
+    Ok(())
+}
+
+async fn run_repair(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let input = args.positional.first().ok_or("repair needs a save file")?;
+
+    let manager = SaveManager::new();
+    let output = &output_for(&manager, args, input, "repaired")?;
+    let report = manager.repair_save(input, output).await?;
+    if report.repairs.is_empty() {
+        print_findings(&report.findings);
+        println!("Nothing to repair, {} was not written", output);
+        return Ok(());
+    }
+    for repair in &report.repairs {
+        println!("fixed: {}", repair);
+    }
+    print_findings(&report.findings);
+    println!("Applied {} repair(s), wrote {}", report.repairs.len(), output);
+    Ok(())
+}
+
+async fn run_export(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let input = args.positional.first().ok_or("export needs a save file")?;
+    let default_output = Path::new(input).with_extension("json").to_string_lossy().to_string();
+    let output = args.value("output").unwrap_or(&default_output);
+
+    let manager = SaveManager::new();
+    manager.export_json(input, output, args.flag("minify")).await?;
+    println!("Wrote {}", output);
+    Ok(())
+}
+
+async fn run_import(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let input = args.positional.first().ok_or("import needs a JSON file")?;
+    let target = args.value("to").ok_or("import needs --to pc or --to switch")?;
+    let default_output = Path::new(input).with_extension("dat").to_string_lossy().to_string();
+    let output = args.value("output").unwrap_or(&default_output);
+
+    let manager = SaveManager::new();
+    let findings = manager.import_json(input, output, target).await?;
+    print_findings(&findings);
+    println!("Wrote {} save {}", target, output);
+    Ok(())
+}
+
+fn print_plan(plan: &SyncPlan) {
+    println!("{} ({}): {} because {}", plan.game, plan.profile, plan.direction, plan.reason);
+    println!("  layout: {}", plan.layout);
+    for read in &plan.reads {
+        println!("  read:   {}", read);
+    }
+    for write in &plan.writes {
+        println!("  write:  {}", write);
+    }
+    if plan.history_snapshot {
+        println!("  a history snapshot is committed afterwards");
+    }
+    if !plan.undoable {
+        println!("  this sync can't be undone from the app");
+    }
+    for conflict in &plan.conflicts {
+        println!("conflict: {}", conflict);
+    }
+    for warning in &plan.warnings {
+        println!("warning: {}", warning);
+    }
+    for blocker in &plan.blockers {
+        println!("blocked: {}", blocker);
+    }
+}
+
+async fn run_sync(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let game = args.positional.first().ok_or("sync needs a game (hk or silksong)")?;
+    let profile = args.value("profile").unwrap_or(DEFAULT_PROFILE);
+    let direction = match args.value("to") {
+        Some("pc") => Some("switch-to-pc".to_string()),
+        Some("switch") => Some("pc-to-switch".to_string()),
+        Some(other) => return Err(format!("--to expects pc or switch, got {}", other).into()),
+        None => None,
+    };
+    let force = args.flag("force");
+
+    let manager = SaveManager::new();
+    if args.flag("dry-run") {
+        let plan = manager.plan_sync(game, profile, direction, force).await?;
+        print_plan(&plan);
+        if plan.is_blocked() {
+            return Err("Sync would be refused, pass --force to run it anyway".into());
+        }
+        return Ok(());
+    }
+
+    let result = manager.sync(game, profile, direction, force).await?;
+    println!("{}", result.message);
+    for warning in &result.warnings {
+        println!("warning: {}", warning);
+    }
+    Ok(())
+}
+
+// accepts a full RFC 3339 timestamp or a plain YYYY-MM-DD date (midnight UTC)
+fn parse_time(name: &str, value: Option<&str>) -> Result<Option<DateTime<Utc>>, Box<dyn std::error::Error>> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(time.with_timezone(&Utc)));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| format!("--{} expects YYYY-MM-DD or an RFC 3339 time", name))?;
+    Ok(date.and_hms_opt(0, 0, 0).map(|midnight| Utc.from_utc_datetime(&midnight)))
+}
+
+fn print_log_entry(entry: &SyncLogEntry) {
+    let scope = match (&entry.game, &entry.profile) {
+        (Some(game), Some(profile)) => format!(" {}/{}", game, profile),
+        (Some(game), None) => format!(" {}", game),
+        _ => String::new(),
+    };
+    let direction = entry.direction.as_deref().map(|d| format!(" {}", d)).unwrap_or_default();
+    println!("{} {}{}{} [{}]", entry.timestamp.format("%Y-%m-%d %H:%M:%S"), entry.operation, scope, direction, entry.outcome);
+    if let (Some(source), Some(destination)) = (&entry.source, &entry.destination) {
+        println!("    {} -> {}", source, destination);
+    }
+    if let Some(snapshot) = &entry.snapshot {
+        println!("    snapshot {}", snapshot);
+    }
+    if let Some(error) = &entry.error {
+        println!("    error: {}", error);
+    }
+    for warning in &entry.warnings {
+        println!("    warning: {}", warning);
+    }
+}
+
+async fn run_history(args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let filter = SyncLogFilter {
+        game: args.value("game").map(|s| s.to_string()),
+        profile: args.value("profile").map(|s| s.to_string()),
+        operation: args.value("operation").map(|s| s.to_string()),
+        outcome: if args.flag("failed") { Some("error".to_string()) } else { None },
+        since: parse_time("since", args.value("since"))?,
+        until: parse_time("until", args.value("until"))?,
+        path: args.value("path").map(|s| s.to_string()),
+        limit: args.value("limit").map(|n| n.parse::<usize>()).transpose().map_err(|_| "--limit needs a number")?,
+    };
+
+    let manager = SaveManager::new();
+    let entries = manager.read_sync_log(&filter).await?;
+    if args.flag("json") {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No matching history entries");
+    }
+    for entry in &entries {
+        print_log_entry(entry);
+    }
+    Ok(())
+}
@@ -31,7 +31,8 @@ impl SaveCrypto {
         result
     }
 
-    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    // decrypts without touching the pkcs7 padding
+    pub fn decrypt_raw(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         if data.len() % 16 != 0 {
             return Err("Invalid encrypted data length".into());
         }
@@ -42,6 +43,11 @@ impl SaveCrypto {
             self.cipher.decrypt_block(&mut block);
             result.extend_from_slice(&block);
         }
+        Ok(result)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut result = self.decrypt_raw(data)?;
 
         if let Some(&padding) = result.last() {
             if padding as usize <= result.len() {
@@ -76,6 +82,10 @@ impl SaveCrypto {
         result
     }
 
+    pub fn has_save_header(data: &[u8]) -> bool {
+        data.starts_with(&SAVE_HEADER)
+    }
+
     pub fn remove_save_header(data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
         if data.len() <= SAVE_HEADER.len() {
             return Err("Data too short to contain header".into());
@@ -0,0 +1,301 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FtpEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub modified: Option<DateTime<Utc>>,
+    pub size: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FtpTarget {
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+    pub directory: String,
+}
+
+pub fn is_ftp_url(path: &str) -> bool {
+    path.to_lowercase().starts_with("ftp://")
+}
+
+impl FtpTarget {
+    // ftp://host[:port][/path], falling back to the game's jksv folder when no path is given
+    pub fn from_url(url: &str, default_directory: &str, username: Option<String>, password: Option<String>) -> Result<Self, Box<dyn std::error::Error>> {
+        let rest = url.get(6..).ok_or("Invalid FTP address")?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse::<u16>().map_err(|_| "Invalid FTP port")?),
+            None => (authority, 21),
+        };
+        if host.is_empty() {
+            return Err("FTP host not configured".into());
+        }
+
+        let directory = match path.trim_end_matches('/') {
+            "" => default_directory.to_string(),
+            path => path.to_string(),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            username: username.unwrap_or_else(|| "anonymous".to_string()),
+            password: password.unwrap_or_default(),
+            directory,
+        })
+    }
+
+    pub fn connect(&self) -> Result<FtpSession, Box<dyn std::error::Error>> {
+        FtpSession::connect(self)
+    }
+}
+
+pub struct FtpSession {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    host: String,
+}
+
+impl FtpSession {
+    fn connect(target: &FtpTarget) -> Result<Self, Box<dyn std::error::Error>> {
+        let address = (target.host.as_str(), target.port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or("Could not resolve FTP host")?;
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+
+        let mut session = Self {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+            host: target.host.clone(),
+        };
+        session.expect(&[220])?;
+
+        let (code, _) = session.command(&format!("USER {}", target.username))?;
+        if code == 331 {
+            session.command_expect(&format!("PASS {}", target.password), &[230, 202])?;
+        } else if code != 230 {
+            return Err(format!("FTP login rejected ({})", code).into());
+        }
+        session.command_expect("TYPE I", &[200])?;
+        Ok(session)
+    }
+
+    fn read_reply(&mut self) -> Result<(u32, String), Box<dyn std::error::Error>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err("FTP connection closed".into());
+        }
+        let code = line.get(..3).and_then(|c| c.parse::<u32>().ok()).ok_or("Malformed FTP reply")?;
+        let mut message = line.get(4..).unwrap_or("").trim_end().to_string();
+
+        // multi-line replies run until "<code> " at the start of a line
+        if line.as_bytes().get(3) == Some(&b'-') {
+            let terminator = format!("{} ", code);
+            loop {
+                line.clear();
+                if self.reader.read_line(&mut line)? == 0 {
+                    return Err("FTP connection closed".into());
+                }
+                message.push('\n');
+                message.push_str(line.trim_end());
+                if line.starts_with(&terminator) {
+                    break;
+                }
+            }
+        }
+
+        Ok((code, message))
+    }
+
+    fn expect(&mut self, codes: &[u32]) -> Result<String, Box<dyn std::error::Error>> {
+        let (code, message) = self.read_reply()?;
+        if codes.contains(&code) {
+            Ok(message)
+        } else {
+            Err(format!("FTP error {}: {}", code, message).into())
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<(u32, String), Box<dyn std::error::Error>> {
+        self.writer.write_all(format!("{}\r\n", command).as_bytes())?;
+        self.read_reply()
+    }
+
+    fn command_expect(&mut self, command: &str, codes: &[u32]) -> Result<String, Box<dyn std::error::Error>> {
+        self.writer.write_all(format!("{}\r\n", command).as_bytes())?;
+        self.expect(codes)
+    }
+
+    fn open_data(&mut self) -> Result<TcpStream, Box<dyn std::error::Error>> {
+        let message = self.command_expect("PASV", &[227])?;
+        // homebrew servers sometimes advertise 0.0.0.0, so always reuse the control host
+        let port = parse_pasv_port(&message)?;
+        let address = (self.host.as_str(), port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or("Could not resolve FTP data address")?;
+        let stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(stream)
+    }
+
+    fn read_data(&mut self, command: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut data_stream = self.open_data()?;
+        self.command_expect(command, &[125, 150])?;
+        let mut data = Vec::new();
+        data_stream.read_to_end(&mut data)?;
+        drop(data_stream);
+        self.expect(&[226, 250])?;
+        Ok(data)
+    }
+
+    pub fn list(&mut self, directory: &str) -> Result<Vec<FtpEntry>, Box<dyn std::error::Error>> {
+        match self.read_data(&format!("MLSD {}", directory)) {
+            Ok(data) => Ok(parse_mlsd(&String::from_utf8_lossy(&data))),
+            Err(_) => {
+                let data = self.read_data(&format!("NLST {}", directory))?;
+                Ok(parse_nlst(&String::from_utf8_lossy(&data)))
+            }
+        }
+    }
+
+    pub fn download(&mut self, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        self.read_data(&format!("RETR {}", path))
+    }
+
+    pub fn upload(&mut self, path: &str, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut data_stream = self.open_data()?;
+        self.command_expect(&format!("STOR {}", path), &[125, 150])?;
+        data_stream.write_all(data)?;
+        drop(data_stream);
+        self.expect(&[226, 250])?;
+        Ok(())
+    }
+
+    pub fn make_dir(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let (code, message) = self.command(&format!("MKD {}", path))?;
+        // 550 usually means it already exists
+        if code == 257 || code == 550 {
+            Ok(())
+        } else {
+            Err(format!("FTP error {}: {}", code, message).into())
+        }
+    }
+
+    pub fn quit(mut self) {
+        let _ = self.command("QUIT");
+    }
+}
+
+// the data port out of "Entering Passive Mode (h1,h2,h3,h4,p1,p2)"
+pub fn parse_pasv_port(message: &str) -> Result<u16, Box<dyn std::error::Error>> {
+    let start = message.find('(').ok_or("Malformed PASV reply")?;
+    let end = message[start..].find(')').ok_or("Malformed PASV reply")? + start;
+    let parts = message[start + 1..end]
+        .split(',')
+        .map(|p| p.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "Malformed PASV reply")?;
+    if parts.len() != 6 {
+        return Err("Malformed PASV reply".into());
+    }
+    Ok(u16::from(parts[4]) * 256 + u16::from(parts[5]))
+}
+
+// servers without MLSD only give us names, so anything that isn't a zip or a save is
+// taken to be a jksv backup folder
+pub fn parse_nlst(listing: &str) -> Vec<FtpEntry> {
+    listing
+        .lines()
+        .map(|line| line.trim().rsplit('/').next().unwrap_or("").to_string())
+        .filter(|name| !name.is_empty() && name != "." && name != "..")
+        .map(|name| {
+            let lower = name.to_lowercase();
+            FtpEntry {
+                is_dir: !lower.ends_with(".zip") && !lower.ends_with(".dat"),
+                modified: None,
+                size: None,
+                name,
+            }
+        })
+        .collect()
+}
+
+pub fn parse_mlsd(listing: &str) -> Vec<FtpEntry> {
+    let mut entries = Vec::new();
+
+    for line in listing.lines() {
+        let Some((facts, name)) = line.split_once(' ') else {
+            continue;
+        };
+        let name = name.trim_end_matches('\r').to_string();
+        let mut kind = String::new();
+        let mut modified = None;
+        let mut size = None;
+
+        for fact in facts.split(';') {
+            let Some((key, value)) = fact.split_once('=') else {
+                continue;
+            };
+            match key.to_lowercase().as_str() {
+                "type" => kind = value.to_lowercase(),
+                "modify" => {
+                    modified = NaiveDateTime::parse_from_str(value.split('.').next().unwrap_or(value), "%Y%m%d%H%M%S")
+                        .ok()
+                        .map(|dt| Utc.from_utc_datetime(&dt));
+                }
+                "size" => size = value.parse::<u64>().ok(),
+                _ => {}
+            }
+        }
+
+        if kind == "cdir" || kind == "pdir" || name == "." || name == ".." {
+            continue;
+        }
+        entries.push(FtpEntry { name, is_dir: kind == "dir", modified, size });
+    }
+
+    entries
+}
+
+// jksv stamps backup names with "YYYY.MM.DD @ HH.MM.SS", which outlives server-side mtimes;
+// the stamp is the local time of whoever made the backup, while mlsd times are utc
+pub fn backup_name_time(name: &str) -> Option<DateTime<Utc>> {
+    backup_name_time_in(name, &Local)
+}
+
+pub fn backup_name_time_in<Tz: TimeZone>(name: &str, zone: &Tz) -> Option<DateTime<Utc>> {
+    let regex = regex::Regex::new(r"(\d{4}\.\d{2}\.\d{2} @ \d{2}\.\d{2}\.\d{2})").ok()?;
+    let stamp = regex.captures(name)?.get(1)?.as_str().to_string();
+    let dt = NaiveDateTime::parse_from_str(&stamp, "%Y.%m.%d @ %H.%M.%S").ok()?;
+    Some(zone.from_local_datetime(&dt).single()?.with_timezone(&Utc))
+}
+
+// backups are either jksv folders or zips; pick the most recent one
+pub fn newest_backup(entries: Vec<FtpEntry>) -> Option<FtpEntry> {
+    entries
+        .into_iter()
+        .filter(|entry| entry.is_dir || entry.name.to_lowercase().ends_with(".zip"))
+        .max_by_key(|entry| entry.modified.or_else(|| backup_name_time(&entry.name)))
+}
+
+pub fn join_path(directory: &str, name: &str) -> String {
+    format!("{}/{}", directory.trim_end_matches('/'), name)
+}
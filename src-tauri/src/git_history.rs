@@ -0,0 +1,77 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+// local git repository of decoded saves, one commit per sync
+pub struct GitHistory {
+    repo_dir: PathBuf,
+}
+
+impl GitHistory {
+    pub fn open(repo_dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let history = Self { repo_dir: repo_dir.to_path_buf() };
+
+        if !repo_dir.join(".git").exists() {
+            fs::create_dir_all(repo_dir)?;
+            history.git(&["init", "--quiet"])?;
+        }
+
+        Ok(history)
+    }
+
+    fn git(&self, args: &[&str]) -> Result<Output, Box<dyn std::error::Error>> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_dir)
+            // commits must not depend on the user's global git identity
+            .args(["-c", "user.name=Hollow Sync", "-c", "user.email=hollow-sync@localhost"])
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+        Ok(output)
+    }
+
+    // writes the file and commits it, returning the new commit id or None if nothing changed
+    pub fn commit_file(&self, relative_path: &str, content: &str, message: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let file_path = self.repo_dir.join(relative_path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&file_path, content)?;
+
+        self.git(&["add", "--", relative_path])?;
+
+        let unchanged = Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_dir)
+            .args(["diff", "--cached", "--quiet"])
+            .status()?
+            .success();
+        if unchanged {
+            return Ok(None);
+        }
+
+        self.git(&["commit", "--quiet", "-m", message])?;
+        let output = self.git(&["rev-parse", "HEAD"])?;
+        Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+
+    // moves a tracked folder and commits the move; does nothing if it was never committed
+    pub fn move_path(&self, from: &str, to: &str, message: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if !self.repo_dir.join(from).exists() {
+            return Ok(None);
+        }
+        if let Some(parent) = self.repo_dir.join(to).parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        self.git(&["mv", "--", from, to])?;
+        self.git(&["commit", "--quiet", "-m", message])?;
+        let output = self.git(&["rev-parse", "HEAD"])?;
+        Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+}
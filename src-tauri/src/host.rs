@@ -0,0 +1,328 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Metadata {
+    pub is_dir: bool,
+    pub len: u64,
+    pub modified: SystemTime,
+}
+
+// everything SaveManager reads from the machine: files, the user's folders and environment
+// variables. RealHost is the machine itself; MemoryHost keeps it all in a map so the sync
+// logic can run in tests without touching the real home directory
+pub trait Host: Send + Sync {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>>;
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+    fn append(&self, path: &Path, data: &[u8]) -> io::Result<()>;
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+    // full paths of the folder's entries, in no particular order
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn create_dir_all(&self, path: &Path) -> io::Result<()>;
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()>;
+    fn remove_file(&self, path: &Path) -> io::Result<()>;
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()>;
+
+    fn home_dir(&self) -> Option<PathBuf>;
+    fn data_dir(&self) -> Option<PathBuf>;
+    fn var(&self, name: &str) -> Option<String>;
+
+    // git and the process list only ever see the real disk, so history is skipped elsewhere
+    fn is_physical(&self) -> bool {
+        true
+    }
+
+    fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    // None for an empty file; the real disk seeks to it instead of reading the whole file
+    fn last_byte(&self, path: &Path) -> io::Result<Option<u8>> {
+        Ok(self.read(path)?.last().copied())
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        let data = self.read(from)?;
+        self.write(to, &data)?;
+        Ok(data.len() as u64)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.metadata(path).is_ok()
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        self.metadata(path).map_or(false, |metadata| metadata.is_dir)
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.metadata(path).map_or(false, |metadata| !metadata.is_dir)
+    }
+}
+
+pub struct RealHost;
+
+impl Host for RealHost {
+    fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        fs::read(path)
+    }
+
+    fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        fs::write(path, data)
+    }
+
+    fn append(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        OpenOptions::new().create(true).append(true).open(path)?.write_all(data)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let metadata = fs::metadata(path)?;
+        Ok(Metadata {
+            is_dir: metadata.is_dir(),
+            len: metadata.len(),
+            modified: metadata.modified()?,
+        })
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(fs::read_dir(path)?.flatten().map(|entry| entry.path()).collect())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::create_dir_all(path)
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        fs::rename(from, to)
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs::remove_file(path)
+    }
+
+    fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        fs::remove_dir_all(path)
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        dirs::home_dir()
+    }
+
+    fn data_dir(&self) -> Option<PathBuf> {
+        dirs::data_dir()
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+
+    fn last_byte(&self, path: &Path) -> io::Result<Option<u8>> {
+        let mut file = fs::File::open(path)?;
+        if file.metadata()?.len() == 0 {
+            return Ok(None);
+        }
+        file.seek(SeekFrom::End(-1))?;
+        let mut last = [0];
+        file.read_exact(&mut last)?;
+        Ok(Some(last[0]))
+    }
+
+    fn copy(&self, from: &Path, to: &Path) -> io::Result<u64> {
+        fs::copy(from, to)
+    }
+}
+
+#[cfg(test)]
+pub use memory::MemoryHost;
+
+#[cfg(test)]
+mod memory {
+    use super::{Host, Metadata};
+    use std::collections::{BTreeMap, HashMap};
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::sync::Mutex;
+    use std::time::{Duration, SystemTime};
+
+    enum Node {
+        Dir,
+        File { data: Vec<u8>, modified: SystemTime },
+    }
+
+    struct State {
+        nodes: BTreeMap<PathBuf, Node>,
+        clock: SystemTime,
+    }
+
+    // every write moves the clock on a second, so later writes are always newer
+    pub struct MemoryHost {
+        state: Mutex<State>,
+        home: PathBuf,
+        vars: HashMap<String, String>,
+    }
+
+    fn not_found(path: &Path) -> io::Error {
+        io::Error::new(io::ErrorKind::NotFound, format!("{} not found", path.display()))
+    }
+
+    impl MemoryHost {
+        pub fn new() -> Self {
+            let home = PathBuf::from("/home/player");
+            let mut nodes = BTreeMap::new();
+            let mut dir = Some(home.as_path());
+            while let Some(path) = dir {
+                nodes.insert(path.to_path_buf(), Node::Dir);
+                dir = path.parent();
+            }
+            Self {
+                state: Mutex::new(State { nodes, clock: SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000) }),
+                home,
+                vars: HashMap::new(),
+            }
+        }
+
+        pub fn with_var(mut self, name: &str, value: &str) -> Self {
+            self.vars.insert(name.to_string(), value.to_string());
+            self
+        }
+
+        // writes a file, creating its folders
+        pub fn add_file(&self, path: impl AsRef<Path>, data: impl AsRef<[u8]>) {
+            let path = path.as_ref();
+            if let Some(parent) = path.parent() {
+                self.create_dir_all(parent).unwrap();
+            }
+            self.write(path, data.as_ref()).unwrap();
+        }
+
+        pub fn set_modified(&self, path: impl AsRef<Path>, time: SystemTime) {
+            let mut state = self.state.lock().unwrap();
+            if let Some(Node::File { modified, .. }) = state.nodes.get_mut(path.as_ref()) {
+                *modified = time;
+            }
+        }
+
+        // the clock after the last write, for setting times relative to it
+        pub fn now(&self) -> SystemTime {
+            self.state.lock().unwrap().clock
+        }
+    }
+
+    impl Host for MemoryHost {
+        fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+            match self.state.lock().unwrap().nodes.get(path) {
+                Some(Node::File { data, .. }) => Ok(data.clone()),
+                Some(Node::Dir) => Err(io::Error::new(io::ErrorKind::Other, format!("{} is a folder", path.display()))),
+                None => Err(not_found(path)),
+            }
+        }
+
+        fn write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let parent_is_dir = path.parent().map_or(false, |parent| matches!(state.nodes.get(parent), Some(Node::Dir)));
+            if !parent_is_dir {
+                return Err(not_found(path.parent().unwrap_or(path)));
+            }
+            if matches!(state.nodes.get(path), Some(Node::Dir)) {
+                return Err(io::Error::new(io::ErrorKind::Other, format!("{} is a folder", path.display())));
+            }
+            state.clock += Duration::from_secs(1);
+            let modified = state.clock;
+            state.nodes.insert(path.to_path_buf(), Node::File { data: data.to_vec(), modified });
+            Ok(())
+        }
+
+        fn append(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+            let mut content = self.read(path).unwrap_or_default();
+            content.extend_from_slice(data);
+            self.write(path, &content)
+        }
+
+        fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+            match self.state.lock().unwrap().nodes.get(path) {
+                Some(Node::Dir) => Ok(Metadata { is_dir: true, len: 0, modified: SystemTime::UNIX_EPOCH }),
+                Some(Node::File { data, modified }) => Ok(Metadata { is_dir: false, len: data.len() as u64, modified: *modified }),
+                None => Err(not_found(path)),
+            }
+        }
+
+        fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+            let state = self.state.lock().unwrap();
+            if !matches!(state.nodes.get(path), Some(Node::Dir)) {
+                return Err(not_found(path));
+            }
+            Ok(state.nodes.keys().filter(|child| child.parent() == Some(path)).cloned().collect())
+        }
+
+        fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            let mut dir = Some(path);
+            while let Some(current) = dir.filter(|p| !p.as_os_str().is_empty()) {
+                match state.nodes.get(current) {
+                    Some(Node::Dir) => break,
+                    Some(Node::File { .. }) => {
+                        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} is a file", current.display())))
+                    }
+                    None => {
+                        state.nodes.insert(current.to_path_buf(), Node::Dir);
+                    }
+                }
+                dir = current.parent();
+            }
+            Ok(())
+        }
+
+        fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            if !state.nodes.contains_key(from) {
+                return Err(not_found(from));
+            }
+            let moved: Vec<PathBuf> = state.nodes.keys().filter(|path| path.starts_with(from)).cloned().collect();
+            for path in moved {
+                let node = state.nodes.remove(&path).unwrap();
+                let target = if path == from { to.to_path_buf() } else { to.join(path.strip_prefix(from).unwrap()) };
+                state.nodes.insert(target, node);
+            }
+            Ok(())
+        }
+
+        fn remove_file(&self, path: &Path) -> io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            match state.nodes.get(path) {
+                Some(Node::File { .. }) => {
+                    state.nodes.remove(path);
+                    Ok(())
+                }
+                Some(Node::Dir) => Err(io::Error::new(io::ErrorKind::Other, format!("{} is a folder", path.display()))),
+                None => Err(not_found(path)),
+            }
+        }
+
+        fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+            let mut state = self.state.lock().unwrap();
+            if !state.nodes.contains_key(path) {
+                return Err(not_found(path));
+            }
+            state.nodes.retain(|node_path, _| !node_path.starts_with(path));
+            Ok(())
+        }
+
+        fn home_dir(&self) -> Option<PathBuf> {
+            Some(self.home.clone())
+        }
+
+        fn data_dir(&self) -> Option<PathBuf> {
+            Some(self.home.join(".local").join("share"))
+        }
+
+        fn var(&self, name: &str) -> Option<String> {
+            self.vars.get(name).cloned()
+        }
+
+        fn is_physical(&self) -> bool {
+            false
+        }
+    }
+}
@@ -2,76 +2,330 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod save_manager;
+mod cli;
 mod crypto;
+mod ftp;
+mod git_history;
+mod host;
+mod process;
+mod progress;
+mod save_data;
+mod save_diff;
+mod save_editor;
+mod save_format;
+mod save_merge;
+mod save_validate;
+mod steam;
+mod sync_config;
+mod sync_log;
+mod sync_plan;
 mod utils;
+mod watcher;
+mod webdav;
 
+#[cfg(test)]
+mod tests;
+
+use progress::{OperationRegistry, Progress};
 use save_manager::SaveManager;
+use tauri::Manager;
+use watcher::WatcherRegistry;
+// long commands take an optional id from the frontend so progress events can be matched up
+// and the operation cancelled; without one the registry makes up a unique id
+fn tracked_manager(app: &tauri::AppHandle, operations: &OperationRegistry, operation: &str, operation_id: &str) -> SaveManager {
+    let cancel = operations.begin(operation_id);
+    let emitter = app.clone();
+    let progress = Progress::new(operation_id, operation, cancel, move |event| {
+        let _ = emitter.emit_all(progress::PROGRESS_EVENT, event);
+    });
+    app.state::<SaveManager>().inner().clone().with_progress(progress)
+}
+
+#[tauri::command]
+async fn detect_saves(
+    app: tauri::AppHandle,
+    operations: tauri::State<'_, OperationRegistry>,
+    game_filter: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<save_manager::DetectedSave>, String> {
+    let operation_id = operations.operation_id("detect", operation_id);
+    let manager = tracked_manager(&app, &operations, "detect", &operation_id);
+    let result = manager.detect_all_saves(game_filter).await
+        .map_err(|e| e.to_string());
+    operations.finish(&operation_id);
+    result
+}
+
+#[tauri::command]
+async fn cancel_operation(operations: tauri::State<'_, OperationRegistry>, operation_id: String) -> Result<bool, String> {
+    Ok(operations.cancel(&operation_id))
+}
+
+// commands that work on a sync pairing take an optional profile, omitting it means the default one
+fn profile_or_default(profile: Option<String>) -> String {
+    profile.unwrap_or_else(|| sync_config::DEFAULT_PROFILE.to_string())
+}
+
+#[tauri::command]
+async fn load_config(manager: tauri::State<'_, SaveManager>, game: String, profile: Option<String>) -> Result<sync_config::SyncConfig, String> {
+    manager.load_config(&game, &profile_or_default(profile)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_config(manager: tauri::State<'_, SaveManager>, game: String, profile: Option<String>, config: sync_config::SyncConfig) -> Result<(), String> {
+    manager.save_config(&game, &profile_or_default(profile), &config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_profiles(manager: tauri::State<'_, SaveManager>, game: String) -> Result<Vec<String>, String> {
+    manager.list_profiles(&game).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn create_profile(manager: tauri::State<'_, SaveManager>, game: String, profile: String) -> Result<sync_config::SyncConfig, String> {
+    manager.create_profile(&game, &profile).await
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
-async fn detect_saves(game_filter: Option<String>) -> Result<Vec<serde_json::Value>, String> {
-    let manager = SaveManager::new();
-    manager.detect_all_saves(game_filter).await
+async fn clone_profile(manager: tauri::State<'_, SaveManager>, game: String, from: String, to: String) -> Result<sync_config::SyncConfig, String> {
+    manager.clone_profile(&game, &from, &to).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn load_config(game: String) -> Result<serde_json::Value, String> {
-    let manager = SaveManager::new();
-    manager.load_config(&game).await
+async fn rename_profile(manager: tauri::State<'_, SaveManager>, registry: tauri::State<'_, WatcherRegistry>, game: String, from: String, to: String) -> Result<(), String> {
+    registry.stop(&game, &from);
+    manager.rename_profile(&game, &from, &to).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn save_config(game: String, config: serde_json::Value) -> Result<(), String> {
-    let manager = SaveManager::new();
-    manager.save_config(&game, config).await
+async fn delete_profile(manager: tauri::State<'_, SaveManager>, registry: tauri::State<'_, WatcherRegistry>, game: String, profile: String) -> Result<(), String> {
+    registry.stop(&game, &profile);
+    manager.delete_profile(&game, &profile).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn sync_saves(game: String, force_direction: Option<String>) -> Result<String, String> {
-    let manager = SaveManager::new();
-    manager.sync(&game, force_direction).await
+async fn sync_saves(
+    app: tauri::AppHandle,
+    operations: tauri::State<'_, OperationRegistry>,
+    game: String,
+    profile: Option<String>,
+    force_direction: Option<String>,
+    force: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<save_manager::SyncResult, String> {
+    let operation_id = operations.operation_id("sync", operation_id);
+    let manager = tracked_manager(&app, &operations, "sync", &operation_id);
+    let result = manager.sync(&game, &profile_or_default(profile), force_direction, force.unwrap_or(false)).await
+        .map_err(|e| e.to_string());
+    operations.finish(&operation_id);
+    result
+}
+
+#[tauri::command]
+async fn plan_sync(
+    manager: tauri::State<'_, SaveManager>,
+    game: String,
+    profile: Option<String>,
+    force_direction: Option<String>,
+    force: Option<bool>,
+) -> Result<sync_plan::SyncPlan, String> {
+    manager.plan_sync(&game, &profile_or_default(profile), force_direction, force.unwrap_or(false)).await
         .map_err(|e| e.to_string())
 }
 
+// stops the watcher first, otherwise the restored side looks newer and gets synced straight back
+#[tauri::command]
+async fn undo_last_sync(
+    manager: tauri::State<'_, SaveManager>,
+    registry: tauri::State<'_, WatcherRegistry>,
+    game: String,
+    profile: Option<String>,
+    force: Option<bool>,
+) -> Result<String, String> {
+    let profile = profile_or_default(profile);
+    registry.stop(&game, &profile);
+    manager.undo_last_sync(&game, &profile, force.unwrap_or(false)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_sync_log(manager: tauri::State<'_, SaveManager>, filter: Option<sync_log::SyncLogFilter>) -> Result<Vec<sync_log::SyncLogEntry>, String> {
+    manager.read_sync_log(&filter.unwrap_or_default()).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_cloud_state(manager: tauri::State<'_, SaveManager>, game: String, save_path: String) -> Result<Option<steam::CloudFileState>, String> {
+    manager.get_cloud_state(&game, &save_path).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_running_game(manager: tauri::State<'_, SaveManager>, game: String) -> Result<Option<String>, String> {
+    manager.get_running_game(&game).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn restore_backup(manager: tauri::State<'_, SaveManager>, game: String, backup_path: String, save_path: String, force: Option<bool>) -> Result<(), String> {
+    manager.restore_backup(&game, &backup_path, &save_path, force.unwrap_or(false)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn sync_from_backup(manager: tauri::State<'_, SaveManager>, game: String, profile: Option<String>, backup_path: String) -> Result<save_manager::SyncResult, String> {
+    manager.sync_from_backup(&game, &profile_or_default(profile), &backup_path).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn list_remote_backups(manager: tauri::State<'_, SaveManager>, game: String, profile: Option<String>) -> Result<Vec<webdav::RemoteBackup>, String> {
+    manager.list_remote_backups(&game, &profile_or_default(profile)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn start_watcher(
+    app: tauri::AppHandle,
+    registry: tauri::State<'_, WatcherRegistry>,
+    game: String,
+    profile: Option<String>,
+) -> Result<Vec<String>, String> {
+    let profile = profile_or_default(profile);
+    registry.stop(&game, &profile);
+    registry.start(app, &game, &profile).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn stop_watcher(registry: tauri::State<'_, WatcherRegistry>, game: String, profile: Option<String>) -> Result<bool, String> {
+    Ok(registry.stop(&game, &profile_or_default(profile)))
+}
+
+#[tauri::command]
+async fn get_watched_games(registry: tauri::State<'_, WatcherRegistry>) -> Result<Vec<String>, String> {
+    Ok(registry.watched_games())
+}
+
 #[tauri::command]
-async fn convert_pc_to_switch(input_path: String, output_path: String) -> Result<(), String> {
-    let manager = SaveManager::new();
+async fn convert_pc_to_switch(manager: tauri::State<'_, SaveManager>, input_path: String, output_path: String) -> Result<(), String> {
     manager.pc_to_switch(&input_path, &output_path).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn convert_switch_to_pc(input_path: String, output_path: String) -> Result<(), String> {
-    let manager = SaveManager::new();
+async fn convert_switch_to_pc(manager: tauri::State<'_, SaveManager>, input_path: String, output_path: String) -> Result<(), String> {
     manager.switch_to_pc(&input_path, &output_path).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn create_backup(save_file: String, output_dir: String) -> Result<(), String> {
-    let manager = SaveManager::new();
-    manager.create_backup(&save_file, &output_dir).await
+async fn diff_saves(manager: tauri::State<'_, SaveManager>, left_path: String, right_path: String) -> Result<save_diff::SaveDiff, String> {
+    manager.diff_saves(&left_path, &right_path).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn merge_saves(
+    manager: tauri::State<'_, SaveManager>,
+    base_path: String,
+    other_path: String,
+    categories: Vec<String>,
+    output_path: String,
+    force: Option<bool>,
+) -> Result<save_merge::MergeResult, String> {
+    manager.merge_saves(&base_path, &other_path, &categories, &output_path, force.unwrap_or(false)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn edit_save(
+    manager: tauri::State<'_, SaveManager>,
+    input_path: String,
+    output_path: String,
+    edits: Vec<save_editor::SaveEdit>,
+) -> Result<Vec<save_validate::Finding>, String> {
+    manager.edit_save(&input_path, &output_path, &edits).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn validate_save(manager: tauri::State<'_, SaveManager>, path: String) -> Result<save_validate::ValidationReport, String> {
+    manager.validate_save(&path).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn repair_save(manager: tauri::State<'_, SaveManager>, input_path: String, output_path: String) -> Result<save_validate::ValidationReport, String> {
+    manager.repair_save(&input_path, &output_path).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn detect_save_variant(manager: tauri::State<'_, SaveManager>, path: String) -> Result<save_format::SaveVariant, String> {
+    manager.detect_save_variant(&path).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn extract_backup(backup_file: String, output_dir: String) -> Result<Vec<String>, String> {
-    let manager = SaveManager::new();
-    manager.extract_backup(&backup_file, &output_dir).await
+async fn export_web_editor(
+    manager: tauri::State<'_, SaveManager>,
+    input_path: String,
+    output_path: Option<String>,
+    variant: Option<save_format::SaveVariant>,
+) -> Result<String, String> {
+    let variant = variant.unwrap_or(save_format::SaveVariant::Pc);
+    let output = generate_output(&manager, &input_path, output_path, variant.extension()).await;
+    manager.export_web_editor(&input_path, &output, variant).await
+        .map_err(|e| e.to_string())?;
+    Ok(output)
+}
+
+#[tauri::command]
+async fn create_backup(manager: tauri::State<'_, SaveManager>, save_file: String, output_dir: String) -> Result<(), String> {
+    manager.create_backup(&save_file, &output_dir).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_file_info(file_path: String) -> Result<serde_json::Value, String> {
-    let manager = SaveManager::new();
+async fn extract_backup(
+    app: tauri::AppHandle,
+    operations: tauri::State<'_, OperationRegistry>,
+    backup_file: String,
+    output_dir: String,
+    operation_id: Option<String>,
+) -> Result<Vec<String>, String> {
+    let operation_id = operations.operation_id("extract", operation_id);
+    let manager = tracked_manager(&app, &operations, "extract", &operation_id);
+    let result = manager.extract_backup(&backup_file, &output_dir).await
+        .map_err(|e| e.to_string());
+    operations.finish(&operation_id);
+    result
+}
+
+#[tauri::command]
+async fn get_file_info(manager: tauri::State<'_, SaveManager>, file_path: String) -> Result<save_manager::FileInfo, String> {
     manager.get_file_info(&file_path).await
         .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-async fn get_saves_dir() -> Result<String, String> {
-    SaveManager::get_saves_dir()
+async fn get_saves_dir(manager: tauri::State<'_, SaveManager>) -> Result<String, String> {
+    manager.get_saves_dir().await
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_history_dir(manager: tauri::State<'_, SaveManager>) -> Result<String, String> {
+    manager.get_history_repo_dir()
         .map(|path| path.to_string_lossy().to_string())
         .map_err(|e| e.to_string())
 }
@@ -116,20 +370,30 @@ async fn open_path(path: String) -> Result<(), String> {
     Ok(())
 }
 
-fn generate_pc_to_switch_output(input_path: &str, input_filename: &str) -> String {
-    if let Ok(saves_dir) = SaveManager::get_saves_dir() {
-        return saves_dir.join(format!("{}.dat", input_filename)).to_string_lossy().to_string();
+const CONVERT_DIRECTIONS: [&str; 5] = ["pc-to-switch", "switch-to-pc", "to-json", "json-to-pc", "json-to-switch"];
+
+// switch saves keep the .dat name because JKSV restores files by name; only the
+// plain json export gets a .json extension
+fn output_extension(direction: &str) -> &'static str {
+    match direction {
+        "to-json" => "json",
+        _ => "dat",
     }
-    
-    format!("{}.dat", input_path.replace(".dat", ""))
 }
 
-fn generate_switch_to_pc_output(input_path: &str, input_filename: &str) -> String {
-    if let Ok(saves_dir) = SaveManager::get_saves_dir() {
-        return saves_dir.join(format!("{}.dat", input_filename)).to_string_lossy().to_string();
+async fn generate_output(manager: &SaveManager, input_path: &str, output_path: Option<String>, extension: &str) -> String {
+    let file_name = format!("{}.{}", get_input_filename(input_path), extension);
+
+    match output_path {
+        Some(path) if manager.host().is_dir(std::path::Path::new(&path)) => {
+            std::path::Path::new(&path).join(file_name).to_string_lossy().to_string()
+        }
+        Some(path) => path,
+        None => match manager.get_saves_dir().await {
+            Ok(saves_dir) => saves_dir.join(file_name).to_string_lossy().to_string(),
+            Err(_) => std::path::Path::new(input_path).with_extension(extension).to_string_lossy().to_string(),
+        },
     }
-    
-    format!("{}.dat", input_path.replace(".dat", ""))
 }
 
 fn get_input_filename(input_path: &str) -> &str {
@@ -139,52 +403,91 @@ fn get_input_filename(input_path: &str) -> &str {
         .unwrap_or("converted")
 }
 
+// without a direction the input is sniffed and converted to the other platform
 #[tauri::command]
-async fn convert_save(input_path: String, output_path: Option<String>, direction: String) -> Result<String, String> {
-    let manager = SaveManager::new();
-    
-    if direction != "pc-to-switch" && direction != "switch-to-pc" {
+async fn convert_save(
+    manager: tauri::State<'_, SaveManager>,
+    input_path: String,
+    output_path: Option<String>,
+    direction: Option<String>,
+    minify: Option<bool>,
+) -> Result<save_format::ConvertResult, String> {
+    let input = manager.sniffed(&input_path).await.map_err(|e| e.to_string())?;
+    let detected = input.detected.clone();
+    let direction = direction.unwrap_or_else(|| detected.conversion_direction().to_string());
+    if !CONVERT_DIRECTIONS.contains(&direction.as_str()) {
         return Err("Invalid conversion direction".to_string());
     }
-    
-    let input_filename = get_input_filename(&input_path);
-    
-    let output = match output_path {
-        Some(path) => path,
-        None => {
-            if direction == "pc-to-switch" {
-                generate_pc_to_switch_output(&input_path, input_filename)
-            } else {
-                generate_switch_to_pc_output(&input_path, input_filename)
-            }
-        }
-    };
-    
-    if direction == "pc-to-switch" {
-        manager.pc_to_switch(&input_path, &output).await
-            .map_err(|e| e.to_string())?;
-    } else {
-        manager.switch_to_pc(&input_path, &output).await
-            .map_err(|e| e.to_string())?;
+    detected.check_direction(&direction)?;
+
+    let output = generate_output(&manager, &input_path, output_path, output_extension(&direction)).await;
+
+    manager.convert(&input_path, input, &output, &direction, minify.unwrap_or(false)).await
+        .map_err(|e| e.to_string())?;
+
+    Ok(save_format::ConvertResult { output, direction, detected })
+}
+
+// release builds use the windows subsystem, so borrow the terminal we were started from
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
     }
-    
-    Ok(output)
 }
 
+#[cfg(not(windows))]
+fn attach_console() {}
+
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    if cli::is_cli_invocation(&args) {
+        attach_console();
+        std::process::exit(cli::run(&args));
+    }
+
     tauri::Builder::default()
+        .manage(SaveManager::new())
+        .manage(WatcherRegistry::default())
+        .manage(OperationRegistry::default())
         .invoke_handler(tauri::generate_handler![
             detect_saves,
             load_config,
             save_config,
+            list_profiles,
+            create_profile,
+            clone_profile,
+            rename_profile,
+            delete_profile,
             sync_saves,
+            plan_sync,
+            undo_last_sync,
+            get_sync_log,
+            get_cloud_state,
+            get_running_game,
+            restore_backup,
+            sync_from_backup,
+            list_remote_backups,
+            start_watcher,
+            stop_watcher,
+            get_watched_games,
             convert_pc_to_switch,
             convert_switch_to_pc,
             convert_save,
+            diff_saves,
+            merge_saves,
+            edit_save,
+            validate_save,
+            repair_save,
+            detect_save_variant,
+            export_web_editor,
             create_backup,
             extract_backup,
+            cancel_operation,
             get_file_info,
             get_saves_dir,
+            get_history_dir,
             open_path
         ])
         .run(tauri::generate_context!())
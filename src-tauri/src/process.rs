@@ -0,0 +1,88 @@
+// basename of an executable path, tolerating windows separators under wine/proton
+fn executable_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+#[cfg(target_os = "linux")]
+fn list_process_names() -> Vec<String> {
+    let mut names = Vec::new();
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return names;
+    };
+
+    for entry in entries.flatten() {
+        let pid_dir = entry.path();
+        let is_pid = entry
+            .file_name()
+            .to_str()
+            .map_or(false, |name| name.chars().all(|c| c.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+
+        // comm is truncated to 15 bytes, so prefer argv[0] from cmdline
+        if let Ok(cmdline) = std::fs::read(pid_dir.join("cmdline")) {
+            if let Some(arg0) = cmdline.split(|&b| b == 0).next().filter(|arg| !arg.is_empty()) {
+                names.push(executable_name(&String::from_utf8_lossy(arg0)).to_string());
+            }
+        }
+        if let Ok(comm) = std::fs::read_to_string(pid_dir.join("comm")) {
+            names.push(comm.trim().to_string());
+        }
+    }
+
+    names
+}
+
+#[cfg(target_os = "windows")]
+fn list_process_names() -> Vec<String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    // CREATE_NO_WINDOW: release builds have no console, so tasklist would flash its own
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let Ok(output) = Command::new("tasklist").args(["/FO", "CSV", "/NH"]).creation_flags(CREATE_NO_WINDOW).output() else {
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split("\",\"").next())
+        .map(|name| name.trim_matches('"').to_string())
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn list_process_names() -> Vec<String> {
+    use std::process::Command;
+
+    let Ok(output) = Command::new("ps").args(["-axo", "comm="]).output() else {
+        return Vec::new();
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| executable_name(line.trim()).to_string())
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn list_process_names() -> Vec<String> {
+    Vec::new()
+}
+
+fn matches_executable(process_name: &str, executable: &str) -> bool {
+    if process_name.eq_ignore_ascii_case(executable) {
+        return true;
+    }
+    // linux comm truncation
+    executable.len() > 15 && process_name.len() == 15 && executable.to_lowercase().starts_with(&process_name.to_lowercase())
+}
+
+pub fn find_running_executable(executables: &[String]) -> Option<String> {
+    let running = list_process_names();
+    executables
+        .iter()
+        .find(|executable| running.iter().any(|name| matches_executable(name, executable)))
+        .cloned()
+}
@@ -0,0 +1,129 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use ts_rs::TS;
+
+pub const PROGRESS_EVENT: &str = "operation-progress";
+pub const CANCELLED: &str = "Operation cancelled";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ProgressEvent {
+    pub operation_id: String,
+    pub operation: String,
+    pub phase: String,
+    pub current_file: Option<String>,
+    #[ts(type = "number")]
+    pub bytes_done: u64,
+    #[ts(type = "number | null")]
+    pub bytes_total: Option<u64>,
+    pub percent: Option<f64>,
+}
+
+pub fn percent(done: u64, total: u64) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some((done as f64 / total as f64 * 100.0).min(100.0))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+type Reporter = Arc<dyn Fn(ProgressEvent) + Send + Sync>;
+
+// what a long operation reports to and checks for cancellation; the default reports
+// nowhere and is never cancelled, which is what the cli and the watcher use
+#[derive(Clone, Default)]
+pub struct Progress {
+    operation_id: String,
+    operation: String,
+    reporter: Option<Reporter>,
+    cancel: CancelToken,
+}
+
+impl Progress {
+    pub fn new(operation_id: &str, operation: &str, cancel: CancelToken, reporter: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            operation_id: operation_id.to_string(),
+            operation: operation.to_string(),
+            reporter: Some(Arc::new(reporter)),
+            cancel,
+        }
+    }
+
+    pub fn report(&self, phase: &str, current_file: Option<&str>, bytes_done: u64, bytes_total: Option<u64>, percent: Option<f64>) {
+        if let Some(reporter) = &self.reporter {
+            reporter(ProgressEvent {
+                operation_id: self.operation_id.clone(),
+                operation: self.operation.clone(),
+                phase: phase.to_string(),
+                current_file: current_file.map(|f| f.to_string()),
+                bytes_done,
+                bytes_total,
+                percent,
+            });
+        }
+    }
+
+    // call between steps; operations stop at the next check and clean up on the way out
+    pub fn check(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.cancel.is_cancelled() {
+            Err(CANCELLED.into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// cancel tokens of the operations the frontend started, by the id it gave them
+#[derive(Default)]
+pub struct OperationRegistry {
+    tokens: Mutex<HashMap<String, CancelToken>>,
+    next_id: AtomicU64,
+}
+
+impl OperationRegistry {
+    // operations started without an id get a fresh one, so two of the same kind running at
+    // once don't replace each other's token; the frontend reads it from the progress events
+    pub fn operation_id(&self, operation: &str, requested: Option<String>) -> String {
+        requested.unwrap_or_else(|| format!("{}-{}", operation, self.next_id.fetch_add(1, Ordering::SeqCst) + 1))
+    }
+
+    pub fn begin(&self, operation_id: &str) -> CancelToken {
+        let token = CancelToken::default();
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(operation_id.to_string(), token.clone());
+        }
+        token
+    }
+
+    pub fn finish(&self, operation_id: &str) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.remove(operation_id);
+        }
+    }
+
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        match self.tokens.lock().ok().and_then(|tokens| tokens.get(operation_id).cloned()) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::crypto::SaveCrypto;
+use crate::host::Host;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SaveSummary {
+    pub version: Option<String>,
+    pub play_time: Option<f64>,
+    pub completion: Option<f64>,
+    #[ts(type = "number | null")]
+    pub geo: Option<i64>,
+    #[ts(type = "number | null")]
+    pub max_health: Option<i64>,
+    pub respawn_scene: Option<String>,
+    #[ts(type = "number | null")]
+    pub permadeath_mode: Option<i64>,
+}
+
+impl SaveSummary {
+    pub fn from_value(save: &Value) -> Self {
+        let player = &save["playerData"];
+        Self {
+            version: player["version"].as_str().map(|s| s.to_string()),
+            play_time: player["playTime"].as_f64(),
+            completion: player["completionPercentage"].as_f64(),
+            geo: player["geo"].as_i64(),
+            max_health: player["maxHealth"].as_i64(),
+            respawn_scene: player["respawnScene"].as_str().map(|s| s.to_string()),
+            permadeath_mode: player["permadeathMode"].as_i64(),
+        }
+    }
+
+    // one line for commit messages and logs, e.g. "12h 03m, 45%, 1200 geo, Town"
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(play_time) = self.play_time {
+            let minutes = (play_time / 60.0) as u64;
+            parts.push(format!("{}h {:02}m", minutes / 60, minutes % 60));
+        }
+        if let Some(completion) = self.completion {
+            parts.push(format!("{}%", completion));
+        }
+        if let Some(geo) = self.geo {
+            parts.push(format!("{} geo", geo));
+        }
+        if let Some(scene) = &self.respawn_scene {
+            parts.push(scene.clone());
+        }
+        if parts.is_empty() {
+            "no summary available".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+}
+
+pub fn decode_pc_save(crypto: &SaveCrypto, data: &[u8]) -> Result<Value, Box<dyn std::error::Error>> {
+    let json = crypto.pc_to_switch(data)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub fn summarize_pc_save(crypto: &SaveCrypto, data: &[u8]) -> Result<SaveSummary, Box<dyn std::error::Error>> {
+    Ok(SaveSummary::from_value(&decode_pc_save(crypto, data)?))
+}
+
+// summaries of pc saves by path, reused while the file's mtime and size are unchanged so
+// rescanning a folder only decodes what was written since; unreadable saves are cached as None
+#[derive(Default)]
+pub struct SummaryCache {
+    entries: Mutex<HashMap<PathBuf, CachedSummary>>,
+}
+
+struct CachedSummary {
+    modified: SystemTime,
+    size: u64,
+    summary: Option<SaveSummary>,
+}
+
+impl SummaryCache {
+    pub fn summarize(&self, crypto: &SaveCrypto, host: &dyn Host, path: &Path, modified: SystemTime, size: u64) -> Option<SaveSummary> {
+        if let Ok(entries) = self.entries.lock() {
+            if let Some(cached) = entries.get(path) {
+                if cached.modified == modified && cached.size == size {
+                    return cached.summary.clone();
+                }
+            }
+        }
+
+        let summary = host.read(path).ok().and_then(|data| summarize_pc_save(crypto, &data).ok());
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(path.to_path_buf(), CachedSummary { modified, size, summary: summary.clone() });
+        }
+        summary
+    }
+}
@@ -0,0 +1,170 @@
+use crate::save_data::SaveSummary;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+pub const CATEGORIES: [&str; 8] = [
+    "progress",
+    "currencies",
+    "collectibles",
+    "bosses",
+    "journal",
+    "map",
+    "charms",
+    "other",
+];
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FieldChange {
+    pub field: String,
+    pub kind: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CategoryDiff {
+    pub category: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SaveDiff {
+    pub left: SaveSummary,
+    pub right: SaveSummary,
+    pub categories: Vec<CategoryDiff>,
+    pub total_changes: usize,
+}
+
+// groups playerData fields the way players think about them; covers both games' naming
+pub fn categorize(field: &str) -> &'static str {
+    let lower = field.to_lowercase();
+
+    if matches!(field, "playTime" | "completionPercentage" | "version" | "permadeathMode" | "respawnScene" | "respawnMarkerName") {
+        return "progress";
+    }
+    if matches!(lower.as_str(), "geo" | "geopool" | "dreamorbs" | "ore" | "rancideggs" | "simplekeys" | "shellshards" | "silk")
+        || lower.starts_with("trinket")
+    {
+        return "currencies";
+    }
+    if lower.starts_with("gotcharm") || lower.starts_with("equippedcharm") || lower.starts_with("charmcost")
+        || lower.starts_with("charmslots") || lower.contains("tool") || lower == "equippedcharms"
+    {
+        return "charms";
+    }
+    if lower.ends_with("defeated") || lower.starts_with("defeated") || lower.starts_with("killedboss") {
+        return "bosses";
+    }
+    if lower.starts_with("killed") || lower.starts_with("kills") || lower.starts_with("newdata") || lower.starts_with("journal") {
+        return "journal";
+    }
+    if lower.starts_with("map") || lower.starts_with("scenes") || lower.starts_with("visited") || lower.starts_with("hasmap")
+        || lower == "hasquill" || lower.starts_with("haspin") || lower.starts_with("hasmarker")
+    {
+        return "map";
+    }
+    if lower.starts_with("has") || lower.starts_with("got") || lower.starts_with("grub") || lower.contains("heartpieces")
+        || lower.contains("vesselfragments") || lower.starts_with("maxhealth") || lower.starts_with("mpreserve")
+        || lower.starts_with("nailsmithupgrade") || lower.starts_with("salubrablessing")
+    {
+        return "collectibles";
+    }
+    "other"
+}
+
+fn change_kind(left: &Value, right: &Value) -> &'static str {
+    match (left, right) {
+        (Value::Null, _) => "added",
+        (_, Value::Null) => "removed",
+        (Value::Bool(false), Value::Bool(true)) => "gained",
+        (Value::Bool(true), Value::Bool(false)) => "lost",
+        (Value::Number(l), Value::Number(r)) => match (l.as_f64(), r.as_f64()) {
+            (Some(l), Some(r)) if r > l => "increased",
+            (Some(l), Some(r)) if r < l => "decreased",
+            _ => "changed",
+        },
+        _ => "changed",
+    }
+}
+
+fn push_change(changes: &mut BTreeMap<&'static str, Vec<FieldChange>>, category: &'static str, field: String, kind: &str, left: Value, right: Value) {
+    changes.entry(category).or_default().push(FieldChange {
+        field,
+        kind: kind.to_string(),
+        left,
+        right,
+    });
+}
+
+// compares playerData field by field; lists such as scenesVisited are compared as sets
+pub fn diff_saves(left: &Value, right: &Value) -> SaveDiff {
+    let empty = serde_json::Map::new();
+    let left_player = left["playerData"].as_object().unwrap_or(&empty);
+    let right_player = right["playerData"].as_object().unwrap_or(&empty);
+
+    let mut fields: Vec<&String> = left_player.keys().chain(right_player.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut changes: BTreeMap<&'static str, Vec<FieldChange>> = BTreeMap::new();
+    for field in fields {
+        let left_value = left_player.get(field).cloned().unwrap_or(Value::Null);
+        let right_value = right_player.get(field).cloned().unwrap_or(Value::Null);
+        if left_value == right_value {
+            continue;
+        }
+
+        let category = categorize(field);
+        match (&left_value, &right_value) {
+            (Value::Array(left_items), Value::Array(right_items)) if is_scalar_list(left_items) && is_scalar_list(right_items) => {
+                for item in right_items.iter().filter(|item| !left_items.contains(item)) {
+                    push_change(&mut changes, category, format!("{}[{}]", field, display_item(item)), "gained", Value::Null, item.clone());
+                }
+                for item in left_items.iter().filter(|item| !right_items.contains(item)) {
+                    push_change(&mut changes, category, format!("{}[{}]", field, display_item(item)), "lost", item.clone(), Value::Null);
+                }
+            }
+            _ => {
+                let kind = change_kind(&left_value, &right_value);
+                push_change(&mut changes, category, field.clone(), kind, left_value, right_value);
+            }
+        }
+    }
+
+    let total_changes = changes.values().map(|c| c.len()).sum();
+    let categories = CATEGORIES
+        .iter()
+        .filter_map(|category| {
+            changes.remove(category).map(|changes| CategoryDiff {
+                category: category.to_string(),
+                changes,
+            })
+        })
+        .collect();
+
+    SaveDiff {
+        left: SaveSummary::from_value(left),
+        right: SaveSummary::from_value(right),
+        categories,
+        total_changes,
+    }
+}
+
+fn is_scalar_list(items: &[Value]) -> bool {
+    items.iter().all(|item| !item.is_object() && !item.is_array())
+}
+
+fn display_item(item: &Value) -> String {
+    match item {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,205 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+const SOUL_PER_VESSEL: i64 = 33;
+
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+#[ts(export)]
+pub enum SaveEdit {
+    Geo {
+        #[ts(type = "number")]
+        value: i64,
+    },
+    Masks {
+        #[ts(type = "number")]
+        value: i64,
+    },
+    SoulVessels {
+        #[ts(type = "number")]
+        value: i64,
+    },
+    CharmOwned { charm: u32, owned: bool },
+    ToolOwned { tool: String, owned: bool },
+    RespawnBench { scene: String, marker: String },
+    SteelSoul { enabled: bool },
+}
+
+pub struct SaveEditor {
+    save: Value,
+}
+
+impl SaveEditor {
+    pub fn new(save: Value) -> Result<Self, Box<dyn std::error::Error>> {
+        if !save["playerData"].is_object() {
+            return Err("Save has no playerData".into());
+        }
+        Ok(Self { save })
+    }
+
+    pub fn into_value(self) -> Value {
+        self.save
+    }
+
+    fn player(&mut self) -> &mut Map<String, Value> {
+        self.save["playerData"].as_object_mut().expect("checked in new")
+    }
+
+    fn int(&mut self, field: &str) -> Option<i64> {
+        self.player().get(field).and_then(|v| v.as_i64())
+    }
+
+    pub fn apply(&mut self, edit: &SaveEdit) -> Result<(), Box<dyn std::error::Error>> {
+        match edit {
+            SaveEdit::Geo { value } => self.set_geo(*value),
+            SaveEdit::Masks { value } => self.set_masks(*value),
+            SaveEdit::SoulVessels { value } => self.set_soul_vessels(*value),
+            SaveEdit::CharmOwned { charm, owned } => self.set_charm_owned(*charm, *owned),
+            SaveEdit::ToolOwned { tool, owned } => self.set_tool_owned(tool, *owned),
+            SaveEdit::RespawnBench { scene, marker } => self.set_respawn_bench(scene, marker),
+            SaveEdit::SteelSoul { enabled } => self.set_steel_soul(*enabled),
+        }
+    }
+
+    // silksong keeps rosaries in the same field
+    pub fn set_geo(&mut self, value: i64) -> Result<(), Box<dyn std::error::Error>> {
+        if !(0..=9_999_999).contains(&value) {
+            return Err("Geo must be between 0 and 9999999".into());
+        }
+        self.player().insert("geo".to_string(), value.into());
+        Ok(())
+    }
+
+    pub fn set_masks(&mut self, value: i64) -> Result<(), Box<dyn std::error::Error>> {
+        let cap = self.int("maxHealthCap").unwrap_or(9);
+        if !(1..=cap).contains(&value) {
+            return Err(format!("Masks must be between 1 and {}", cap).into());
+        }
+
+        // health is refilled so the save doesn't load with empty masks
+        let player = self.player();
+        player.insert("maxHealthBase".to_string(), value.into());
+        player.insert("maxHealth".to_string(), value.into());
+        player.insert("health".to_string(), value.into());
+        if player.contains_key("prevHealth") {
+            player.insert("prevHealth".to_string(), value.into());
+        }
+        // a new mask resets any partial progress towards the next one
+        if player.contains_key("heartPieces") {
+            player.insert("heartPieces".to_string(), 0.into());
+        }
+        Ok(())
+    }
+
+    pub fn set_soul_vessels(&mut self, value: i64) -> Result<(), Box<dyn std::error::Error>> {
+        if !(0..=3).contains(&value) {
+            return Err("Soul vessels must be between 0 and 3".into());
+        }
+
+        let reserve_max = value * SOUL_PER_VESSEL;
+        let reserve = self.int("MPReserve").unwrap_or(0).min(reserve_max);
+        let player = self.player();
+        player.insert("MPReserveMax".to_string(), reserve_max.into());
+        player.insert("MPReserve".to_string(), reserve.into());
+        if player.contains_key("vesselFragments") {
+            player.insert("vesselFragments".to_string(), 0.into());
+        }
+        Ok(())
+    }
+
+    pub fn set_charm_owned(&mut self, charm: u32, owned: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let got_key = format!("gotCharm_{}", charm);
+        if !self.player().contains_key(&got_key) {
+            return Err(format!("Unknown charm: {}", charm).into());
+        }
+
+        let player = self.player();
+        player.insert(got_key, owned.into());
+        if !owned {
+            // an unowned charm can't stay equipped
+            player.insert(format!("equippedCharm_{}", charm), false.into());
+            if let Some(equipped) = player.get_mut("equippedCharms").and_then(|v| v.as_array_mut()) {
+                equipped.retain(|id| id.as_u64() != Some(charm as u64));
+            }
+            recount_charm_slots(player);
+        }
+
+        let owned_count = player
+            .iter()
+            .filter(|(key, value)| key.starts_with("gotCharm_") && value.as_bool() == Some(true))
+            .count();
+        if player.contains_key("charmsOwned") {
+            player.insert("charmsOwned".to_string(), owned_count.into());
+        }
+        Ok(())
+    }
+
+    // silksong stores tools as Tools.savedData[{ Name, Data: { IsUnlocked, ... } }]
+    pub fn set_tool_owned(&mut self, tool: &str, owned: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let tools = self
+            .player()
+            .get_mut("Tools")
+            .and_then(|tools| tools.get_mut("savedData"))
+            .and_then(|data| data.as_array_mut())
+            .ok_or("Save has no tool list")?;
+
+        let entry = tools
+            .iter_mut()
+            .find(|entry| entry["Name"].as_str() == Some(tool))
+            .ok_or_else(|| format!("Unknown tool: {}", tool))?;
+        let data = entry["Data"].as_object_mut().ok_or("Malformed tool entry")?;
+        data.insert("IsUnlocked".to_string(), owned.into());
+        if !owned && data.contains_key("IsHidden") {
+            data.insert("IsHidden".to_string(), true.into());
+        }
+        Ok(())
+    }
+
+    pub fn set_respawn_bench(&mut self, scene: &str, marker: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if scene.is_empty() || marker.is_empty() {
+            return Err("Respawn scene and marker are required".into());
+        }
+
+        let player = self.player();
+        player.insert("respawnScene".to_string(), scene.into());
+        player.insert("respawnMarkerName".to_string(), marker.into());
+        // 1 tells the game to spawn sitting on a bench
+        player.insert("respawnType".to_string(), 1.into());
+        if player.contains_key("hazardRespawnFacingRight") {
+            player.insert("hazardRespawnFacingRight".to_string(), true.into());
+        }
+        Ok(())
+    }
+
+    pub fn set_steel_soul(&mut self, enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+        // 0 normal, 1 steel soul, 2 steel soul that has died
+        self.player().insert("permadeathMode".to_string(), (if enabled { 1 } else { 0 }).into());
+        Ok(())
+    }
+}
+
+// notches in use and overcharming follow from the charms still equipped, so they're worked out
+// again from each one's charmCost_N rather than adjusted
+fn recount_charm_slots(player: &mut Map<String, Value>) {
+    let equipped: Vec<u64> = match player.get("equippedCharms").and_then(|v| v.as_array()) {
+        Some(list) => list.iter().filter_map(|id| id.as_u64()).collect(),
+        None => player
+            .iter()
+            .filter(|(_, value)| value.as_bool() == Some(true))
+            .filter_map(|(key, _)| key.strip_prefix("equippedCharm_")?.parse().ok())
+            .collect(),
+    };
+    let filled: i64 = equipped
+        .iter()
+        .map(|id| player.get(&format!("charmCost_{}", id)).and_then(|v| v.as_i64()).unwrap_or(0))
+        .sum();
+
+    if player.contains_key("charmSlotsFilled") {
+        player.insert("charmSlotsFilled".to_string(), filled.into());
+    }
+    let slots = player.get("charmSlots").and_then(|v| v.as_i64());
+    if let (Some(slots), true) = (slots, player.contains_key("overcharmed")) {
+        player.insert("overcharmed".to_string(), (filled > slots).into());
+    }
+}
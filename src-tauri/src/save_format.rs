@@ -0,0 +1,232 @@
+use crate::crypto::SaveCrypto;
+use crate::host::Host;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// the shapes a single save file turns up in; bloodorca's web editor downloads
+// either `Pc` or `Json`, older copies of it dropped the trailer byte, and some
+// tools pass the encrypted base64 around with no BinaryFormatter wrapper at all
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum SaveVariant {
+    Pc,
+    PcNoTrailer,
+    Base64,
+    Json,
+}
+
+// the variants the web editor can open
+pub const WEB_EDITOR_VARIANTS: [SaveVariant; 2] = [SaveVariant::Pc, SaveVariant::Json];
+
+impl SaveVariant {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SaveVariant::Pc => "PC save",
+            SaveVariant::PcNoTrailer => "PC save without trailer",
+            SaveVariant::Base64 => "encrypted base64 without header",
+            SaveVariant::Json => "plain JSON",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SaveVariant::Json => "json",
+            _ => "dat",
+        }
+    }
+}
+
+fn strip_bom(data: &[u8]) -> &[u8] {
+    data.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(data)
+}
+
+fn compact_base64(data: &[u8]) -> Option<String> {
+    let text: String = std::str::from_utf8(data).ok()?.chars().filter(|c| !c.is_whitespace()).collect();
+    let is_base64 = !text.is_empty()
+        && text.len() % 4 == 0
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='));
+    if is_base64 { Some(text) } else { None }
+}
+
+// only looks at the framing; decode() is what proves the content is a save
+pub fn detect_variant(data: &[u8]) -> Option<SaveVariant> {
+    let body = strip_bom(data);
+    if body.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{') {
+        return Some(SaveVariant::Json);
+    }
+    if SaveCrypto::has_save_header(data) {
+        return Some(if data.last() == Some(&11) { SaveVariant::Pc } else { SaveVariant::PcNoTrailer });
+    }
+    compact_base64(data).map(|_| SaveVariant::Base64)
+}
+
+// returns the save's json text exactly as stored, so converting never reorders fields
+pub fn decode(crypto: &SaveCrypto, data: &[u8]) -> Result<(SaveVariant, String), Box<dyn std::error::Error>> {
+    let variant = detect_variant(data).ok_or("File is not a PC save, an encrypted save or save JSON")?;
+
+    let text = match variant {
+        SaveVariant::Pc => crypto.pc_to_switch(data)?,
+        SaveVariant::PcNoTrailer => {
+            let mut framed = data.to_vec();
+            framed.push(11);
+            crypto.pc_to_switch(&framed)?
+        }
+        SaveVariant::Base64 => {
+            let encrypted = general_purpose::STANDARD.decode(compact_base64(data).unwrap_or_default())?;
+            String::from_utf8(crypto.decrypt(&encrypted)?)
+                .map_err(|_| "Encrypted data did not decrypt to text, is this a Hollow Knight save?")?
+        }
+        SaveVariant::Json => String::from_utf8(strip_bom(data).to_vec())?,
+    };
+
+    serde_json::from_str::<serde_json::Value>(&text)
+        .map_err(|e| format!("Decoded {} is not valid save JSON: {}", variant.label(), e))?;
+    Ok((variant, text))
+}
+
+pub fn encode(crypto: &SaveCrypto, json: &str, variant: SaveVariant) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    Ok(match variant {
+        SaveVariant::Pc => crypto.switch_to_pc(json)?,
+        SaveVariant::PcNoTrailer => {
+            let mut data = crypto.switch_to_pc(json)?;
+            data.pop();
+            data
+        }
+        SaveVariant::Base64 => general_purpose::STANDARD.encode(crypto.encrypt(json.as_bytes())).into_bytes(),
+        SaveVariant::Json => json.as_bytes().to_vec(),
+    })
+}
+
+// what convert_save reports back: the outer shape of the input and the save inside it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export)]
+pub enum InputFormat {
+    Pc,
+    PcNoTrailer,
+    Base64,
+    Json,
+    JksvFolder,
+    JksvZip,
+    Zip,
+}
+
+impl From<SaveVariant> for InputFormat {
+    fn from(variant: SaveVariant) -> Self {
+        match variant {
+            SaveVariant::Pc => InputFormat::Pc,
+            SaveVariant::PcNoTrailer => InputFormat::PcNoTrailer,
+            SaveVariant::Base64 => InputFormat::Base64,
+            SaveVariant::Json => InputFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DetectedFormat {
+    pub format: InputFormat,
+    pub variant: SaveVariant,
+    pub entry: Option<String>,
+}
+
+impl DetectedFormat {
+    // switch json (which is also what JKSV backups hold) goes to pc, anything encrypted goes to switch
+    pub fn conversion_direction(&self) -> &'static str {
+        match self.variant {
+            SaveVariant::Json => "switch-to-pc",
+            _ => "pc-to-switch",
+        }
+    }
+
+    // catches the usual mix-up of picking the wrong direction before it becomes a decode error
+    pub fn check_direction(&self, direction: &str) -> Result<(), String> {
+        let is_json = self.variant == SaveVariant::Json;
+        match direction {
+            "pc-to-switch" if is_json => Err("This is already a Switch save (plain JSON), convert it with switch-to-pc".to_string()),
+            "switch-to-pc" if !is_json => Err(format!("This is a {}, convert it with pc-to-switch", self.variant.label())),
+            "json-to-pc" | "json-to-switch" if self.format != InputFormat::Json => {
+                Err(format!("{} expects a plain JSON file, this is a {}", direction, self.variant.label()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ConvertResult {
+    pub output: String,
+    pub direction: String,
+    pub detected: DetectedFormat,
+}
+
+pub struct SniffedSave {
+    pub detected: DetectedFormat,
+    pub json: String,
+}
+
+fn is_jksv_save_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    name.strip_prefix("user")
+        .and_then(|rest| rest.strip_suffix(".dat"))
+        .map_or(false, |slot| !slot.is_empty() && slot.chars().all(|c| c.is_ascii_digit()))
+}
+
+// likely save files first so a stray readme that happens to be base64 never wins
+fn candidate_order(names: &mut [String]) {
+    names.sort_by_key(|name| {
+        let file_name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+        (!is_jksv_save_name(&file_name), !(file_name.ends_with(".dat") || file_name.contains("user")), name.clone())
+    });
+}
+
+// figures out what a dropped file or folder is without being told, trying each file inside
+// containers until one decodes as a save
+pub fn sniff(crypto: &SaveCrypto, host: &dyn Host, path: &str) -> Result<SniffedSave, Box<dyn std::error::Error>> {
+    let file_path = std::path::Path::new(path);
+
+    if host.is_dir(file_path) {
+        let mut names: Vec<String> = host.read_dir(file_path)?
+            .into_iter()
+            .filter(|entry| host.is_file(entry))
+            .filter_map(|entry| entry.file_name().and_then(|n| n.to_str()).map(|n| n.to_string()))
+            .collect();
+        candidate_order(&mut names);
+
+        for name in &names {
+            let data = host.read(&file_path.join(name))?;
+            if let Ok((variant, json)) = decode(crypto, &data) {
+                let detected = DetectedFormat { format: InputFormat::JksvFolder, variant, entry: Some(name.clone()) };
+                return Ok(SniffedSave { detected, json });
+            }
+        }
+        return Err(format!("No save found in folder {} ({} files checked)", path, names.len()).into());
+    }
+
+    let data = host.read(file_path)?;
+    if data.starts_with(b"PK\x03\x04") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(&data))?;
+        let mut names: Vec<String> = archive.file_names().filter(|n| !n.ends_with('/')).map(|n| n.to_string()).collect();
+        candidate_order(&mut names);
+
+        for name in &names {
+            let mut content = Vec::new();
+            std::io::Read::read_to_end(&mut archive.by_name(name)?, &mut content)?;
+            if let Ok((variant, json)) = decode(crypto, &content) {
+                let format = if is_jksv_save_name(name) { InputFormat::JksvZip } else { InputFormat::Zip };
+                let detected = DetectedFormat { format, variant, entry: Some(name.clone()) };
+                return Ok(SniffedSave { detected, json });
+            }
+        }
+        return Err(format!("No save found in zip {} ({} files checked)", path, names.len()).into());
+    }
+
+    let (variant, json) = decode(crypto, &data)?;
+    let detected = DetectedFormat { format: variant.into(), variant, entry: None };
+    Ok(SniffedSave { detected, json })
+}
@@ -1,124 +1,377 @@
 use crate::crypto::SaveCrypto;
+use crate::ftp::{self, FtpTarget};
+use crate::git_history::GitHistory;
+use crate::host::{Host, RealHost};
+use crate::process;
+use crate::progress::{self, Progress};
+use crate::save_data::{self, SaveSummary, SummaryCache};
+use crate::save_diff::{self, SaveDiff};
+use crate::save_editor::{SaveEdit, SaveEditor};
+use crate::save_format::{self, SaveVariant, SniffedSave};
+use crate::save_merge::{self, MergeResult};
+use crate::save_validate::{self, Finding, ValidationReport};
+use crate::steam::{self, CloudFileState};
+use crate::sync_config::{self, SyncConfig};
+use crate::sync_log::{self, SyncLogEntry, SyncLogFilter};
+use crate::sync_plan::{self, SyncPlan};
 use crate::utils::{Games, get_game_paths, is_jksv_format};
+use crate::webdav::{self, WebDavClient};
+use serde::Serialize;
 use serde_json::Value;
-use std::fs::{self, File};
-use std::io::{Read};
+use std::io::{Cursor, Read, Write};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use ts_rs::TS;
 use zip::{ZipArchive};
 use chrono::{DateTime, Utc};
 
-#[derive(Debug)]
-pub struct SaveFile {
+// one save slot found by detect_saves
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct DetectedSave {
     pub slot: u8,
     pub file: String,
     pub path: PathBuf,
     pub modified: DateTime<Utc>,
+    #[ts(type = "number")]
     pub size: u64,
     pub directory: PathBuf,
     pub game: String,
     pub game_name: String,
     pub game_display_name: String,
     pub base_path: PathBuf,
+    pub steam_id: Option<String>,
+    pub steam_name: Option<String>,
+    pub restore_points: Vec<RestorePoint>,
+    pub cloud: Option<CloudFileState>,
 }
 
+// copies the game leaves next to a slot, e.g. user1_1.5.78.11833.dat after an update or user1.dat.bak
+#[derive(Debug, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RestorePoint {
+    pub slot: u8,
+    pub file: String,
+    pub path: PathBuf,
+    pub kind: String,
+    pub version: Option<String>,
+    pub modified: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub size: u64,
+    pub summary: Option<SaveSummary>,
+}
+
+// a local file, or the newest backup on a remote switch target (which also has a name)
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct FileInfo {
+    pub exists: bool,
+    pub modified: Option<DateTime<Utc>>,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SyncResult {
+    pub direction: String,
+    pub message: String,
+    pub snapshot: Option<String>,
+    pub undoable: bool,
+    pub warnings: Vec<String>,
+}
+
+// where the switch side of a sync lives
+#[derive(Clone)]
+pub enum SwitchTarget {
+    Local(String),
+    WebDav(WebDavClient),
+    Ftp(FtpTarget),
+}
+
+impl SwitchTarget {
+    pub fn from_config(game: &str, config: &SyncConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let switch_save = config.switch_save.as_str();
+        if switch_save.is_empty() {
+            return Err("Switch save path not configured".into());
+        }
+
+        if webdav::is_webdav_url(switch_save) {
+            let client = WebDavClient::new(switch_save, config.webdav_username.clone(), config.webdav_password.clone());
+            return Ok(SwitchTarget::WebDav(client));
+        }
+
+        if ftp::is_ftp_url(switch_save) {
+            let game_config = Games::get_config(game).ok_or("Unknown game")?;
+            let default_directory = format!("/JKSV/{}", game_config.jksv_folder);
+            let target = FtpTarget::from_url(switch_save, &default_directory, config.ftp_username.clone(), config.ftp_password.clone())?;
+            return Ok(SwitchTarget::Ftp(target));
+        }
+
+        Ok(SwitchTarget::Local(switch_save.to_string()))
+    }
+}
+
+// file names scan_for_saves recognises, compiled once per manager instead of per folder
+struct SavePatterns {
+    user: regex::Regex,
+    versioned: regex::Regex,
+    bak: regex::Regex,
+}
+
+impl SavePatterns {
+    fn new() -> Self {
+        Self {
+            user: regex::Regex::new(r"^user([1-4])\.dat$").expect("valid save pattern"),
+            versioned: regex::Regex::new(r"^user([1-4])_(\d+(?:\.\d+)*)\.dat$").expect("valid save pattern"),
+            bak: regex::Regex::new(r"^user([1-4])(?:_(\d+(?:\.\d+)*))?(?:\.dat)?\.bak\d*$").expect("valid save pattern"),
+        }
+    }
+}
+
+// the app keeps one of these in tauri state; clones share the cipher and summary cache
+#[derive(Clone)]
 pub struct SaveManager {
-    crypto: SaveCrypto,
+    host: Arc<dyn Host>,
+    crypto: Arc<SaveCrypto>,
+    patterns: Arc<SavePatterns>,
+    summaries: Arc<SummaryCache>,
+    progress: Progress,
 }
 
 impl SaveManager {
     pub fn new() -> Self {
+        Self::with_host(Arc::new(RealHost))
+    }
+
+    pub fn with_host(host: Arc<dyn Host>) -> Self {
         Self {
-            crypto: SaveCrypto::new(),
+            host,
+            crypto: Arc::new(SaveCrypto::new()),
+            patterns: Arc::new(SavePatterns::new()),
+            summaries: Arc::new(SummaryCache::default()),
+            progress: Progress::default(),
         }
     }
 
-    fn get_config_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let data_dir = dirs::data_dir()
+    // for code outside the manager that still has to look at the same disk, like the watcher
+    pub fn host(&self) -> Arc<dyn Host> {
+        self.host.clone()
+    }
+
+    pub fn with_progress(mut self, progress: Progress) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // filesystem, zip, git and network work runs on tokio's blocking pool so a slow sd card or
+    // share doesn't stall the runtime threads every other command is waiting on; the task is
+    // spawned straight away, before the returned future is polled, so several can run at once
+    fn blocking<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(SaveManager) -> Result<T, Box<dyn std::error::Error>> + Send + 'static,
+    ) -> impl std::future::Future<Output = Result<T, Box<dyn std::error::Error>>> {
+        let manager = self.clone();
+        let task = tokio::task::spawn_blocking(move || work(manager).map_err(|e| e.to_string()));
+        async move { task.await?.map_err(|e| e.into()) }
+    }
+
+    fn get_data_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(self.host
+            .data_dir()
             .ok_or("Failed to get user data directory")?
-            .join("HollowSync");
-        let config_dir = data_dir.join("config");
-        fs::create_dir_all(&config_dir)?;
+            .join("HollowSync"))
+    }
+
+    fn get_config_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let config_dir = self.get_data_dir()?.join("config");
+        self.host.create_dir_all(&config_dir)?;
         Ok(config_dir)
     }
 
-    pub fn get_saves_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
-        let data_dir = dirs::data_dir()
-            .ok_or("Failed to get user data directory")?
-            .join("HollowSync");
-        let saves_dir = data_dir.join("saves");
-        fs::create_dir_all(&saves_dir)?;
-        Ok(saves_dir)
+    pub async fn get_saves_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.blocking(|manager| {
+            let saves_dir = manager.get_data_dir()?.join("saves");
+            manager.host.create_dir_all(&saves_dir)?;
+            Ok(saves_dir)
+        })
+        .await
     }
 
-    pub async fn detect_all_saves(&self, game_filter: Option<String>) -> Result<Vec<Value>, Box<dyn std::error::Error>> {
-        let search_paths = get_game_paths(game_filter.as_deref());
-        let mut all_saves = Vec::new();
-        let mut seen_paths = std::collections::HashSet::new();
+    pub fn get_sync_log_path(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(self.get_data_dir()?.join("sync-log.jsonl"))
+    }
 
-        for (game, search_path) in search_paths {
-            if search_path.exists() {
-                let saves = self.find_save_files(&search_path, &game).await?;
-                for save in saves {
-                    let normalized = save.path.to_string_lossy().to_lowercase();
-                    if !seen_paths.contains(&normalized) {
-                        seen_paths.insert(normalized);
-                        all_saves.push(save);
-                    }
+    fn get_undo_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(self.get_data_dir()?.join("undo"))
+    }
+
+    pub async fn read_sync_log(&self, filter: &SyncLogFilter) -> Result<Vec<SyncLogEntry>, Box<dyn std::error::Error>> {
+        let filter = filter.clone();
+        self.blocking(move |manager| sync_log::read(manager.host.as_ref(), &manager.get_sync_log_path()?, &filter)).await
+    }
+
+    // hash and summary of whatever save sits at `path`; remote or unreadable locations have neither
+    fn save_state(&self, path: &str) -> (Option<String>, Option<SaveSummary>) {
+        if webdav::is_webdav_url(path) || ftp::is_ftp_url(path) {
+            return (None, None);
+        }
+        match self.sniff_save(path) {
+            Ok(sniffed) => Self::sniffed_state(&sniffed),
+            Err(_) => (None, None),
+        }
+    }
+
+    fn sniffed_state(sniffed: &SniffedSave) -> (Option<String>, Option<SaveSummary>) {
+        let summary = serde_json::from_str(&sniffed.json).ok().map(|save| SaveSummary::from_value(&save));
+        (Some(steam::sha1_hex(sniffed.json.as_bytes())), summary)
+    }
+
+    // a source the caller already hashed is not read again
+    async fn capture_before(&self, entry: &mut SyncLogEntry) {
+        let source = entry.source.clone().filter(|_| entry.source_sha.is_none());
+        let destination = entry.destination.clone();
+        let states = self
+            .blocking(move |manager| Ok((source.map(|path| manager.save_state(&path)), destination.map(|path| manager.save_state(&path)))))
+            .await;
+        if let Ok((source, destination)) = states {
+            if let Some(state) = source {
+                (entry.source_sha, entry.source_summary) = state;
+            }
+            if let Some(state) = destination {
+                (entry.destination_sha_before, entry.destination_summary_before) = state;
+            }
+        }
+    }
+
+    // the entry is written on the blocking pool; only the error text of `result` is held
+    // while it is, so the returned future stays Send
+    fn record<T>(
+        &self,
+        mut entry: SyncLogEntry,
+        result: Result<T, Box<dyn std::error::Error>>,
+    ) -> impl std::future::Future<Output = Result<T, Box<dyn std::error::Error>>> {
+        entry.finish(&result);
+        let result = result.map_err(|e| e.to_string());
+        let succeeded = result.is_ok();
+        let logged = self.blocking(move |manager| {
+            if succeeded {
+                if let Some(destination) = entry.destination.clone() {
+                    entry.destination_sha_after = manager.save_state(&destination).0;
                 }
             }
+            sync_log::append(manager.host.as_ref(), &manager.get_sync_log_path()?, &entry)
+        });
+        async move {
+            // the log must never turn a finished operation into a failure
+            let _ = logged.await;
+            Ok(result?)
         }
+    }
 
-        all_saves.sort_by(|a, b| b.modified.cmp(&a.modified));
-        let mut result = Vec::new();
-        for save in all_saves {
-            let value = serde_json::json!({
-                "slot": save.slot,
-                "file": save.file,
-                "path": save.path.to_string_lossy(),
-                "modified": save.modified.to_rfc3339(),
-                "size": save.size,
-                "directory": save.directory.to_string_lossy(),
-                "game": save.game,
-                "gameName": save.game_name,
-                "gameDisplayName": save.game_display_name,
-                "basePath": save.base_path.to_string_lossy()
-            });
-            result.push(value);
+    async fn logged<T>(
+        &self,
+        mut entry: SyncLogEntry,
+        run: impl std::future::Future<Output = Result<T, Box<dyn std::error::Error>>>,
+    ) -> Result<T, Box<dyn std::error::Error>> {
+        self.capture_before(&mut entry).await;
+        let result = run.await;
+        self.record(entry, result).await
+    }
+
+    pub fn get_history_repo_dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        Ok(self.get_data_dir()?.join("history"))
+    }
+
+    pub async fn detect_all_saves(&self, game_filter: Option<String>) -> Result<Vec<DetectedSave>, Box<dyn std::error::Error>> {
+        self.detect_saves_in(get_game_paths(self.host.as_ref(), game_filter.as_deref())).await
+    }
+
+    // every search path is scanned at once, results are merged in the order the paths were given
+    pub async fn detect_saves_in(&self, search_paths: Vec<(String, PathBuf)>) -> Result<Vec<DetectedSave>, Box<dyn std::error::Error>> {
+        let scans: Vec<_> = search_paths
+            .into_iter()
+            .map(|(game, search_path)| {
+                let label = search_path.to_string_lossy().to_string();
+                let scan = self.blocking(move |manager| {
+                    if !manager.host.exists(&search_path) {
+                        return Ok(Vec::new());
+                    }
+                    manager.find_save_files(&search_path, &game)
+                });
+                (label, scan)
+            })
+            .collect();
+
+        let mut all_saves = Vec::new();
+        let mut seen_paths = std::collections::HashSet::new();
+        let total = scans.len() as u64;
+        for (index, (label, scan)) in scans.into_iter().enumerate() {
+            let saves = scan.await?;
+            self.progress.report("scanning", Some(&label), 0, None, progress::percent(index as u64 + 1, total));
+            for save in saves {
+                let normalized = save.path.to_string_lossy().to_lowercase();
+                if !seen_paths.contains(&normalized) {
+                    seen_paths.insert(normalized);
+                    all_saves.push(save);
+                }
+            }
         }
 
-        Ok(result)
+        all_saves.sort_by_key(|save| std::cmp::Reverse(save.modified));
+        self.progress.report("done", None, 0, None, Some(100.0));
+        Ok(all_saves)
     }
 
-    async fn find_save_files(&self, base_path: &Path, game_key: &str) -> Result<Vec<SaveFile>, Box<dyn std::error::Error>> {
+    fn find_save_files(&self, base_path: &Path, game_key: &str) -> Result<Vec<DetectedSave>, Box<dyn std::error::Error>> {
         let mut results = Vec::new();
         let game_config = Games::get_config(game_key).ok_or("Unknown game")?;
 
-        self.scan_for_saves(base_path, &mut results).await?;
-        self.scan_user_directories(base_path, &mut results).await?;
+        self.progress.check()?;
+        self.scan_for_saves(base_path, None, &mut results)?;
+        self.scan_user_directories(base_path, &mut results)?;
+
+        let steam_users = if results.iter().any(|save| save.steam_id.is_some()) {
+            steam::load_login_users(self.host.as_ref())
+        } else {
+            Default::default()
+        };
+
         for save in &mut results {
             save.game = game_key.to_string();
             save.game_name = game_config.name.clone();
             save.game_display_name = game_config.display_name.clone();
             save.base_path = base_path.to_path_buf();
+            save.steam_name = save
+                .steam_id
+                .as_deref()
+                .and_then(|id| steam::resolve_persona_name(&steam_users, id));
+            save.cloud = steam::get_cloud_state(self.host.as_ref(), game_config.steam_app_id, &save.path);
         }
 
-        results.sort_by(|a, b| b.modified.cmp(&a.modified));
+        results.sort_by_key(|save| std::cmp::Reverse(save.modified));
 
         Ok(results)
     }
 
-    async fn scan_user_directories(&self, base_path: &Path, results: &mut Vec<SaveFile>) -> Result<(), Box<dyn std::error::Error>> {
-        let Ok(entries) = fs::read_dir(base_path) else {
+    fn scan_user_directories(&self, base_path: &Path, results: &mut Vec<DetectedSave>) -> Result<(), Box<dyn std::error::Error>> {
+        let Ok(entries) = self.host.read_dir(base_path) else {
             return Ok(());
         };
         
-        for entry in entries.flatten() {
-            if !entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+        for sub_path in entries {
+            if !self.host.is_dir(&sub_path) {
                 continue;
             }
             
-            let name = entry.file_name();
-            let Some(name_str) = name.to_str() else {
+            let Some(name_str) = sub_path.file_name().and_then(|name| name.to_str()) else {
                 continue;
             };
             
@@ -126,37 +379,101 @@ impl SaveManager {
                 continue;
             }
             
-            let sub_path = entry.path();
-            self.scan_for_saves(&sub_path, results).await?;
+            // silksong keeps one folder per steam user, named after the steam id
+            self.progress.check()?;
+            self.progress.report("scanning", sub_path.to_str(), 0, None, None);
+            self.scan_for_saves(&sub_path, Some(name_str), results)?;
         }
         
         Ok(())
     }
 
-    async fn scan_for_saves(&self, scan_path: &Path, results: &mut Vec<SaveFile>) -> Result<(), Box<dyn std::error::Error>> {
-        let user_regex = regex::Regex::new(r"^user([1-4])\.dat$")?;
-        
-        let Ok(entries) = fs::read_dir(scan_path) else {
+    fn scan_for_saves(
+        &self,
+        scan_path: &Path,
+        steam_id: Option<&str>,
+        results: &mut Vec<DetectedSave>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let Ok(entries) = self.host.read_dir(scan_path) else {
             return Ok(());
         };
         
-        for entry in entries.flatten() {
-            if let Some(save_file) = self.try_parse_save_file(&entry, &user_regex, scan_path)? {
-                results.push(save_file);
+        let mut found = Vec::new();
+        let mut restore_points = Vec::new();
+        for entry in entries {
+            if let Some(mut save_file) = self.try_parse_save_file(&entry, &self.patterns.user, scan_path)? {
+                save_file.steam_id = steam_id.map(|id| id.to_string());
+                found.push(save_file);
+            } else if let Some(point) = self.try_parse_restore_point(&entry, &self.patterns.versioned, "versioned")? {
+                restore_points.push(point);
+            } else if let Some(point) = self.try_parse_restore_point(&entry, &self.patterns.bak, "bak")? {
+                restore_points.push(point);
+            }
+        }
+
+        restore_points.sort_by_key(|point| std::cmp::Reverse(point.modified));
+        for point in restore_points {
+            if let Some(save_file) = found.iter_mut().find(|save| save.slot == point.slot) {
+                save_file.restore_points.push(point);
             }
         }
+        results.extend(found);
         
         Ok(())
     }
 
+    fn try_parse_restore_point(
+        &self,
+        entry: &Path,
+        regex: &regex::Regex,
+        kind: &str,
+    ) -> Result<Option<RestorePoint>, Box<dyn std::error::Error>> {
+        let Some(name_str) = entry.file_name().and_then(|name| name.to_str()) else {
+            return Ok(None);
+        };
+
+        let Some(captures) = regex.captures(name_str) else {
+            return Ok(None);
+        };
+
+        let Some(Ok(slot)) = captures.get(1).map(|m| m.as_str().parse::<u8>()) else {
+            return Ok(None);
+        };
+
+        let Ok(metadata) = self.host.metadata(entry) else {
+            return Ok(None);
+        };
+
+        if metadata.is_dir {
+            return Ok(None);
+        }
+
+        let modified = metadata.modified;
+        let summary = self.summaries.summarize(&self.crypto, self.host.as_ref(), entry, modified, metadata.len);
+        let version = captures
+            .get(2)
+            .map(|m| m.as_str().to_string())
+            .or_else(|| summary.as_ref().and_then(|s| s.version.clone()));
+
+        Ok(Some(RestorePoint {
+            slot,
+            file: name_str.to_string(),
+            path: entry.to_path_buf(),
+            kind: kind.to_string(),
+            version,
+            modified: DateTime::<Utc>::from(modified),
+            size: metadata.len,
+            summary,
+        }))
+    }
+
     fn try_parse_save_file(
         &self,
-        entry: &std::fs::DirEntry,
+        entry: &Path,
         user_regex: &regex::Regex,
         scan_path: &Path,
-    ) -> Result<Option<SaveFile>, Box<dyn std::error::Error>> {
-        let file_name = entry.file_name();
-        let Some(name_str) = file_name.to_str() else {
+    ) -> Result<Option<DetectedSave>, Box<dyn std::error::Error>> {
+        let Some(name_str) = entry.file_name().and_then(|name| name.to_str()) else {
             return Ok(None);
         };
         
@@ -172,186 +489,796 @@ impl SaveManager {
             return Ok(None);
         };
         
-        let Ok(metadata) = entry.metadata() else {
+        let Ok(metadata) = self.host.metadata(entry) else {
             return Ok(None);
         };
         
-        let modified = metadata.modified()?;
-        let modified_utc = DateTime::<Utc>::from(modified);
+        let modified_utc = DateTime::<Utc>::from(metadata.modified);
         
-        Ok(Some(SaveFile {
+        Ok(Some(DetectedSave {
             slot,
             file: name_str.to_string(),
-            path: entry.path(),
+            path: entry.to_path_buf(),
             modified: modified_utc,
-            size: metadata.len(),
+            size: metadata.len,
             directory: scan_path.to_path_buf(),
             game: String::new(),
             game_name: String::new(),
             game_display_name: String::new(),
             base_path: PathBuf::new(),
+            steam_id: None,
+            steam_name: None,
+            restore_points: Vec::new(),
+            cloud: None,
         }))
     }
 
-    pub async fn load_config(&self, game: &str) -> Result<Value, Box<dyn std::error::Error>> {
-        let config_dir = Self::get_config_dir()?;
-        let config_file = config_dir.join(format!("{}-sync.json", game));
-        match fs::read_to_string(&config_file) {
-            Ok(content) => Ok(serde_json::from_str(&content)?),
-            Err(_) => Ok(serde_json::json!({
-                "pcSave": "",
-                "switchSave": "",
-                "lastSync": null
-            }))
+    // old files are migrated and broken ones quarantined; only a file from a newer build fails
+    pub async fn load_config(&self, game: &str, profile: &str) -> Result<SyncConfig, Box<dyn std::error::Error>> {
+        sync_config::validate_profile_name(profile)?;
+        let (game, profile) = (game.to_string(), profile.to_string());
+        self.blocking(move |manager| {
+            Ok(sync_config::load(manager.host.as_ref(), &sync_config::profile_file(&manager.get_config_dir()?, &game, &profile))?)
+        })
+        .await
+    }
+
+    pub async fn save_config(&self, game: &str, profile: &str, config: &SyncConfig) -> Result<(), Box<dyn std::error::Error>> {
+        sync_config::validate_profile_name(profile)?;
+        let (game, profile, config) = (game.to_string(), profile.to_string(), config.clone());
+        self.blocking(move |manager| {
+            sync_config::save(manager.host.as_ref(), &sync_config::profile_file(&manager.get_config_dir()?, &game, &profile), &config)
+        })
+        .await
+    }
+
+    pub async fn list_profiles(&self, game: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let game = game.to_string();
+        self.blocking(move |manager| Ok(sync_config::list_profiles(manager.host.as_ref(), &manager.get_config_dir()?, &game))).await
+    }
+
+    fn new_profile_file(&self, game: &str, profile: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        sync_config::validate_profile_name(profile)?;
+        let file = sync_config::profile_file(&self.get_config_dir()?, game, profile);
+        if profile == sync_config::DEFAULT_PROFILE || self.host.exists(&file) {
+            return Err(format!("Profile \"{}\" already exists", profile).into());
         }
+        Ok(file)
+    }
+
+    pub async fn create_profile(&self, game: &str, profile: &str) -> Result<SyncConfig, Box<dyn std::error::Error>> {
+        self.write_new_profile(game, profile, SyncConfig::default()).await
     }
 
-    pub async fn save_config(&self, game: &str, config: Value) -> Result<(), Box<dyn std::error::Error>> {
-        let config_dir = Self::get_config_dir()?;
-        let config_file = config_dir.join(format!("{}-sync.json", game));
-        let content = serde_json::to_string_pretty(&config)?;
-        fs::write(&config_file, content)?;
+    async fn write_new_profile(&self, game: &str, profile: &str, config: SyncConfig) -> Result<SyncConfig, Box<dyn std::error::Error>> {
+        let (game, profile) = (game.to_string(), profile.to_string());
+        self.blocking(move |manager| {
+            sync_config::save(manager.host.as_ref(), &manager.new_profile_file(&game, &profile)?, &config)?;
+            Ok(config)
+        })
+        .await
+    }
+
+    // the copy starts unsynced so its first sync compares timestamps instead of trusting the source's
+    pub async fn clone_profile(&self, game: &str, from: &str, to: &str) -> Result<SyncConfig, Box<dyn std::error::Error>> {
+        let config = SyncConfig {
+            last_sync: None,
+            ..self.load_config(game, from).await?
+        };
+        self.write_new_profile(game, to, config).await
+    }
+
+    pub async fn rename_profile(&self, game: &str, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let (game, from, to) = (game.to_string(), from.to_string(), to.to_string());
+        self.blocking(move |manager| manager.move_profile(&game, &from, &to)).await
+    }
+
+    fn move_profile(&self, game: &str, from: &str, to: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if from == sync_config::DEFAULT_PROFILE {
+            return Err("The default profile can't be renamed".into());
+        }
+        sync_config::validate_profile_name(from)?;
+        let source = sync_config::profile_file(&self.get_config_dir()?, game, from);
+        if !self.host.exists(&source) {
+            return Err(format!("Profile \"{}\" does not exist", from).into());
+        }
+        self.host.rename(&source, &self.new_profile_file(game, to)?)?;
+
+        // history is best effort like in sync, the profile itself has already moved
+        if !self.host.is_physical() {
+            return Ok(());
+        }
+        if let Ok(history) = GitHistory::open(&self.get_history_repo_dir()?) {
+            let message = format!("Rename profile {} to {}", from, to);
+            let _ = history.move_path(&Self::history_folder(game, from), &Self::history_folder(game, to), &message);
+        }
         Ok(())
     }
 
-    pub async fn get_file_info(&self, file_path: &str) -> Result<Value, Box<dyn std::error::Error>> {
-        match fs::metadata(file_path) {
-            Ok(metadata) => {
-                let modified = metadata.modified()?;
-                let modified_utc = DateTime::<Utc>::from(modified);
-                Ok(serde_json::json!({
-                    "exists": true,
-                    "modified": modified_utc.to_rfc3339(),
-                    "size": metadata.len()
-                }))
-            }
-            Err(_) => Ok(serde_json::json!({
-                "exists": false,
-                "modified": null,
-                "size": 0
-            }))
+    // only the config goes; commits already in the history repository are kept
+    pub async fn delete_profile(&self, game: &str, profile: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if profile == sync_config::DEFAULT_PROFILE {
+            return Err("The default profile can't be deleted".into());
         }
+        sync_config::validate_profile_name(profile)?;
+        let (game, profile) = (game.to_string(), profile.to_string());
+        self.blocking(move |manager| {
+            let file = sync_config::profile_file(&manager.get_config_dir()?, &game, &profile);
+            if !manager.host.exists(&file) {
+                return Err(format!("Profile \"{}\" does not exist", profile).into());
+            }
+            manager.host.remove_file(&file)?;
+            Ok(())
+        })
+        .await
     }
 
-    pub async fn sync(&self, game: &str, force_direction: Option<String>) -> Result<String, Box<dyn std::error::Error>> {
-        let config = self.load_config(game).await?;
-        
-        let pc_save = config["pcSave"].as_str().ok_or("PC save path not configured")?;
-        let switch_save = config["switchSave"].as_str().ok_or("Switch save path not configured")?;
+    pub async fn get_file_info(&self, file_path: &str) -> Result<FileInfo, Box<dyn std::error::Error>> {
+        let file_path = file_path.to_string();
+        self.blocking(move |manager| {
+            Ok(match manager.host.metadata(Path::new(&file_path)) {
+                Ok(metadata) => FileInfo {
+                    exists: true,
+                    modified: Some(DateTime::<Utc>::from(metadata.modified)),
+                    size: metadata.len,
+                    name: None,
+                },
+                Err(_) => FileInfo::default(),
+            })
+        })
+        .await
+    }
 
-        if pc_save.is_empty() || switch_save.is_empty() {
+    pub async fn sync(&self, game: &str, profile: &str, force_direction: Option<String>, force: bool) -> Result<SyncResult, Box<dyn std::error::Error>> {
+        let mut entry = SyncLogEntry::new("sync").for_profile(game, profile);
+        let result = self.run_sync(game, profile, force_direction, force, &mut entry).await;
+        self.record(entry, result).await
+    }
+
+    async fn run_sync(
+        &self,
+        game: &str,
+        profile: &str,
+        force_direction: Option<String>,
+        force: bool,
+        entry: &mut SyncLogEntry,
+    ) -> Result<SyncResult, Box<dyn std::error::Error>> {
+        let mut config = self.load_config(game, profile).await?;
+        if !config.is_configured() {
             return Err("Save sync not configured".into());
         }
+        let pc_save = config.pc_save.as_str();
+        let switch_name = config.switch_file_name();
+
+        let target = SwitchTarget::from_config(game, &config)?;
+        self.progress.report("planning", None, 0, None, Some(0.0));
+        let plan = self.build_plan(game, profile, &config, &target, force_direction, force).await?;
+        if plan.is_blocked() {
+            return Err(plan.blockers.join(" ").into());
+        }
+        // last point to stop before the undo copy is taken and the write starts
+        self.progress.check()?;
+        let direction = plan.direction;
+        entry.set_paths(Some(&direction), &plan.source, &plan.destination);
+        self.capture_before(entry).await;
+
+        let written = self.written_path(&direction, &target, pc_save, switch_name.as_deref());
+        let (sync_direction, source, sync_target) = (direction.clone(), pc_save.to_string(), target.clone());
+        self.write_with_undo(game, profile, written, entry, || {
+            self.progress.report("writing", Some(&plan.destination), 0, None, Some(40.0));
+            self.blocking(move |manager| match sync_direction.as_str() {
+                "pc-to-switch" => manager.sync_pc_to_switch(&source, &sync_target, switch_name.as_deref()),
+                "switch-to-pc" => manager.sync_switch_to_pc(&sync_target, &source, switch_name.as_deref()),
+                _ => Err("Invalid sync direction".into()),
+            })
+        })
+        .await?;
 
+        if config.git_history {
+            self.progress.report("history", None, 0, None, Some(90.0));
+        }
+        self.snapshot_history(game, profile, &direction, pc_save, &config, entry).await;
+
+        config.last_sync = Some(Utc::now());
+        self.save_config(game, profile, &config).await?;
+
+        self.progress.report("done", None, 0, None, Some(100.0));
+        Ok(SyncResult {
+            message: format!("Sync completed: {}", direction),
+            direction,
+            snapshot: entry.snapshot.clone(),
+            undoable: entry.undo_copy.is_some() || entry.destination_created,
+            warnings: entry.warnings.clone(),
+        })
+    }
+
+    pub async fn plan_sync(&self, game: &str, profile: &str, force_direction: Option<String>, force: bool) -> Result<SyncPlan, Box<dyn std::error::Error>> {
+        let config = self.load_config(game, profile).await?;
+        if !config.is_configured() {
+            return Err("Save sync not configured".into());
+        }
+        let target = SwitchTarget::from_config(game, &config)?;
+        self.build_plan(game, profile, &config, &target, force_direction, force).await
+    }
+
+    // reads file metadata and remote listings only, so it is safe to call for a dry run
+    async fn build_plan(
+        &self,
+        game: &str,
+        profile: &str,
+        config: &SyncConfig,
+        target: &SwitchTarget,
+        force_direction: Option<String>,
+        force: bool,
+    ) -> Result<SyncPlan, Box<dyn std::error::Error>> {
+        let pc_save = config.pc_save.as_str();
+        let switch_save = config.switch_save.as_str();
         let pc_info = self.get_file_info(pc_save).await?;
-        let switch_info = self.get_file_info(switch_save).await?;
-
-        let pc_exists = pc_info["exists"].as_bool().unwrap_or(false);
-        let switch_exists = switch_info["exists"].as_bool().unwrap_or(false);
-
-        if !pc_exists && !switch_exists {
-            return Err("No save files found at configured paths".into());
-        }
-
-        let direction = match force_direction {
-            Some(dir) => dir,
-            None => {
-                if !pc_exists {
-                    "switch-to-pc".to_string()
-                } else if !switch_exists {
-                    "pc-to-switch".to_string()
-                } else {
-                    // compare timestamps to decide sync direction
-                    let pc_modified = pc_info["modified"].as_str().unwrap_or("");
-                    let switch_modified = switch_info["modified"].as_str().unwrap_or("");
-                    
-                    if pc_modified > switch_modified {
-                        "pc-to-switch".to_string()
-                    } else {
-                        "switch-to-pc".to_string()
-                    }
+        let switch_info = self.get_switch_info(target).await?;
+
+        let (direction, reason) = sync_plan::choose_direction(&pc_info, &switch_info, force_direction)?;
+        let conflicts = sync_plan::find_conflicts(config.last_sync, &pc_info, &switch_info, &direction);
+        let switch_name = config.switch_file_name();
+        let pc_name = Path::new(pc_save).file_name().and_then(|n| n.to_str()).ok_or("Invalid save file name")?;
+        let file_name = switch_name.as_deref().unwrap_or(pc_name);
+        let written = self.written_path(&direction, target, pc_save, switch_name.as_deref());
+        let remote_backup = switch_info.name.as_deref().unwrap_or("newest backup");
+
+        let mut warnings = Vec::new();
+        let mut blockers = Vec::new();
+        let (reads, writes, layout) = if direction == "pc-to-switch" {
+            if let Some(cloud) = self.get_cloud_state(game, pc_save).await? {
+                if cloud.differs_from_local() {
+                    warnings.push(format!("Steam Cloud has a different copy of {} ({}), the PC save being synced may be out of date", cloud.file, cloud.status));
                 }
             }
+            let new_backup = Self::jksv_backup_name();
+            let (writes, layout) = match target {
+                SwitchTarget::Local(switch_save) if is_jksv_format(self.host.as_ref(), switch_save) => {
+                    (written.iter().cloned().collect(), format!("JKSV backup folder holding {}", file_name))
+                }
+                SwitchTarget::Local(_) => (written.iter().cloned().collect(), "plain Switch JSON file".to_string()),
+                SwitchTarget::WebDav(_) => (
+                    vec![format!("{}/{}.zip", switch_save.trim_end_matches('/'), new_backup)],
+                    format!("new JKSV backup zip on the WebDAV server holding {}", file_name),
+                ),
+                SwitchTarget::Ftp(ftp_target) => (
+                    vec![ftp::join_path(&ftp::join_path(&ftp_target.directory, &new_backup), file_name)],
+                    format!("new JKSV backup folder on the FTP server holding {}", file_name),
+                ),
+            };
+            (vec![pc_save.to_string()], writes, layout)
+        } else {
+            if let Err(e) = self.ensure_pc_writable(game, pc_save).await {
+                if force { warnings.push(e.to_string()) } else { blockers.push(e.to_string()) }
+            }
+            let read = match target {
+                SwitchTarget::Local(switch_save) if self.host.is_dir(Path::new(switch_save)) => {
+                    let save_files = self.find_save_files_in_directory(switch_save)?;
+                    pick_save_file(&save_files, switch_name.as_deref(), "No save files found in backup directory")?.clone()
+                }
+                SwitchTarget::Local(switch_save) if is_jksv_format(self.host.as_ref(), switch_save) => format!("{} ({} inside the zip)", switch_save, file_name),
+                SwitchTarget::Local(switch_save) => switch_save.clone(),
+                SwitchTarget::WebDav(_) => format!("{} on the WebDAV server", remote_backup),
+                SwitchTarget::Ftp(_) => format!("{} on the FTP server", remote_backup),
+            };
+            (vec![read], vec![pc_save.to_string()], "encrypted PC save".to_string())
         };
 
-        match direction.as_str() {
-            "pc-to-switch" => {
-                self.sync_pc_to_switch(pc_save, switch_save).await?;
-                "PC save synced to Switch format".to_string()
+        let (source, destination) = if direction == "pc-to-switch" {
+            (pc_save.to_string(), written.clone().unwrap_or_else(|| switch_save.to_string()))
+        } else {
+            (switch_save.to_string(), pc_save.to_string())
+        };
+
+        Ok(SyncPlan {
+            game: game.to_string(),
+            profile: profile.to_string(),
+            direction,
+            reason,
+            source,
+            destination,
+            reads,
+            writes,
+            layout,
+            history_snapshot: config.git_history,
+            undoable: written.is_some(),
+            conflicts,
+            warnings,
+            blockers,
+        })
+    }
+
+    // the local file a sync in `direction` overwrites; remote targets get a new backup instead
+    fn written_path(&self, direction: &str, target: &SwitchTarget, pc_save: &str, switch_name: Option<&str>) -> Option<String> {
+        if direction == "switch-to-pc" {
+            return Some(pc_save.to_string());
+        }
+        match target {
+            SwitchTarget::Local(switch_save) if is_jksv_format(self.host.as_ref(), switch_save) => {
+                let pc_name = Path::new(pc_save).file_name()?.to_str()?;
+                Some(Path::new(switch_save).join(switch_name.unwrap_or(pc_name)).to_string_lossy().to_string())
             }
-            "switch-to-pc" => {
-                self.sync_switch_to_pc(switch_save, pc_save).await?;
-                "Switch save synced to PC format".to_string()
+            SwitchTarget::Local(switch_save) => Some(switch_save.clone()),
+            _ => None,
+        }
+    }
+
+    // only the newest sync can be undone, so each profile keeps a single pre-sync copy. The
+    // new copy sits next to the previous one until the write has succeeded, so a failed or
+    // cancelled sync leaves the last successful one undoable
+    async fn write_with_undo<F>(
+        &self,
+        game: &str,
+        profile: &str,
+        written: Option<String>,
+        entry: &mut SyncLogEntry,
+        write: impl FnOnce() -> F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: std::future::Future<Output = Result<(), Box<dyn std::error::Error>>>,
+    {
+        let undo_dir = self.get_undo_dir()?.join(Self::history_folder(game, profile));
+        if let Some(written) = written {
+            self.progress.report("keeping undo copy", Some(&written), 0, None, Some(20.0));
+            entry.destination_created = !self.host.exists(Path::new(&written));
+            let stash_dir = undo_dir.clone();
+            entry.undo_copy = self.blocking(move |manager| manager.stash_for_undo(&stash_dir, &written)).await?;
+        }
+
+        let result = write().await.map_err(|e| e.to_string());
+        let copy = entry.undo_copy.clone().map(PathBuf::from);
+        // stale copies only cost disk space, tidying up must not fail the sync
+        let _ = match &result {
+            Ok(()) => self.blocking(move |manager| manager.prune_undo(&undo_dir, copy.as_deref())).await,
+            Err(_) => {
+                entry.undo_copy = None;
+                entry.destination_created = false;
+                self.blocking(move |manager| Ok(copy.map_or(Ok(()), |copy| manager.host.remove_file(&copy))?)).await
+            }
+        };
+        Ok(result?)
+    }
+
+    fn stash_for_undo(&self, undo_dir: &Path, written: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if !self.host.is_file(Path::new(written)) {
+            return Ok(None);
+        }
+
+        self.host.create_dir_all(undo_dir)?;
+        let file_name = Path::new(written).file_name().and_then(|n| n.to_str()).unwrap_or("save");
+        let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+        let copy = (1..)
+            .map(|n| if n == 1 { format!("{}-{}", stamp, file_name) } else { format!("{}-{}-{}", stamp, n, file_name) })
+            .map(|name| undo_dir.join(name))
+            .find(|copy| !self.host.exists(copy))
+            .ok_or("No free name for the undo copy")?;
+        self.host.copy(Path::new(written), &copy)?;
+        Ok(Some(copy.to_string_lossy().to_string()))
+    }
+
+    fn prune_undo(&self, undo_dir: &Path, keep: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.host.is_dir(undo_dir) {
+            return Ok(());
+        }
+        for entry in self.host.read_dir(undo_dir)? {
+            if Some(entry.as_path()) != keep && self.host.is_file(&entry) {
+                self.host.remove_file(&entry)?;
             }
-            _ => return Err("Invalid sync direction".into())
+        }
+        Ok(())
+    }
+
+    pub async fn undo_last_sync(&self, game: &str, profile: &str, force: bool) -> Result<String, Box<dyn std::error::Error>> {
+        let filter = SyncLogFilter {
+            game: Some(game.to_string()),
+            profile: Some(profile.to_string()),
+            operation: Some("sync".to_string()),
+            outcome: Some("success".to_string()),
+            limit: Some(1),
+            ..SyncLogFilter::default()
         };
+        let last = self.read_sync_log(&filter).await?.into_iter().next().ok_or("No sync to undo for this profile")?;
+        if last.reverted.is_some() {
+            return Err("The last sync has already been undone".into());
+        }
+
+        let destination = last.destination.clone().ok_or("The last sync did not record its destination")?;
+        if webdav::is_webdav_url(&destination) || ftp::is_ftp_url(&destination) {
+            return Err("Syncs to a remote Switch target add a new backup instead of overwriting one, delete it on the server to undo".into());
+        }
+        match &last.undo_copy {
+            Some(copy) if !self.host.is_file(Path::new(copy)) => {
+                return Err(format!("The pre-sync copy {} is gone, the last sync can't be undone", copy).into());
+            }
+            None if !last.destination_created => {
+                return Err("No pre-sync copy was kept for the last sync, it can't be undone".into());
+            }
+            _ => {}
+        }
+        if !force {
+            let current = destination.clone();
+            if self.blocking(move |manager| Ok(manager.save_state(&current).0)).await? != last.destination_sha_after {
+                return Err(format!(
+                    "{} has changed since the sync at {}. Force the undo to discard those changes.",
+                    destination,
+                    last.timestamp.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ).into());
+            }
+            if last.direction.as_deref() == Some("switch-to-pc") {
+                self.ensure_pc_writable(game, &destination).await?;
+            }
+        }
+
+        let mut entry = SyncLogEntry::new("undo").for_profile(game, profile);
+        entry.set_paths(last.direction.as_deref(), last.undo_copy.as_deref().unwrap_or_default(), &destination);
+        entry.reverts = Some(last.timestamp);
+        self.capture_before(&mut entry).await;
+
+        let (undo_copy, restored) = (last.undo_copy.clone(), destination.clone());
+        let result = self
+            .blocking(move |manager| match undo_copy {
+                Some(copy) => manager.host.copy(Path::new(&copy), Path::new(&restored))
+                    .map(|_| format!("Restored {} to before the last sync", restored))
+                    .map_err(|e| e.into()),
+                None => manager.host.remove_file(Path::new(&restored))
+                    .map(|_| format!("Removed {}, which the last sync created", restored))
+                    .map_err(|e| e.into()),
+            })
+            .await
+            .map_err(|e| e.to_string());
+
+        if result.is_ok() {
+            let config = self.load_config(game, profile).await?;
+            self.snapshot_history(game, profile, "undo", &config.pc_save, &config, &mut entry).await;
+        }
+        self.record(entry, result.map_err(|e| e.into())).await
+    }
+
+    // the default profile keeps the original `<game>/` folder so existing history continues
+    fn history_folder(game: &str, profile: &str) -> String {
+        if profile == sync_config::DEFAULT_PROFILE {
+            game.to_string()
+        } else {
+            format!("{}/profiles/{}", game, profile)
+        }
+    }
+
+    // history is best effort, a missing git install must not fail the sync, but a snapshot
+    // that wasn't saved is reported back instead of silently dropped
+    async fn snapshot_history(&self, game: &str, profile: &str, direction: &str, synced: &str, config: &SyncConfig, entry: &mut SyncLogEntry) {
+        if !config.git_history {
+            return;
+        }
+        let (game, profile, direction, synced, pc_save) =
+            (game.to_string(), profile.to_string(), direction.to_string(), synced.to_string(), config.pc_save.clone());
+        match self.blocking(move |manager| manager.commit_to_history(&game, &profile, &direction, &synced, &pc_save)).await {
+            Ok(snapshot) => entry.snapshot = snapshot,
+            Err(e) => entry.warnings.push(format!("The history snapshot was not saved: {}", e)),
+        }
+    }
+
+    // `synced` is the local file holding what both sides have after the sync: the pc save,
+    // or the restore point a backup sync sent over. It is filed under the pc save's name
+    fn commit_to_history(&self, game: &str, profile: &str, direction: &str, synced: &str, pc_save: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        if !self.host.is_physical() {
+            return Ok(None);
+        }
+        let save = save_data::decode_pc_save(&self.crypto, &self.host.read(Path::new(synced))?)?;
+        let summary = SaveSummary::from_value(&save);
+        let display_name = Games::get_config(game).map(|config| config.display_name).unwrap_or_else(|| game.to_string());
+        let file_stem = Path::new(pc_save).file_stem().and_then(|s| s.to_str()).unwrap_or("save");
+
+        let message = format!(
+            "{} {} ({}, {})\n\n{}\nSource: {}",
+            display_name,
+            direction,
+            profile,
+            file_stem,
+            summary.describe(),
+            synced
+        );
+
+        let history = GitHistory::open(&self.get_history_repo_dir()?)?;
+        history.commit_file(
+            &format!("{}/{}.json", Self::history_folder(game, profile), file_stem),
+            &serde_json::to_string_pretty(&save)?,
+            &message,
+        )
+    }
+
+    // reads steam's vdf files, so it runs on the blocking pool like file access
+    pub async fn get_cloud_state(&self, game: &str, save_path: &str) -> Result<Option<CloudFileState>, Box<dyn std::error::Error>> {
+        let game_config = Games::get_config(game).ok_or("Unknown game")?;
+        let save_path = PathBuf::from(save_path);
+        self.blocking(move |manager| Ok(steam::get_cloud_state(manager.host.as_ref(), game_config.steam_app_id, &save_path))).await
+    }
+
+    // lists processes with ps or tasklist
+    pub async fn get_running_game(&self, game: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+        let game_config = Games::get_config(game).ok_or("Unknown game")?;
+        if !self.host.is_physical() {
+            return Ok(None);
+        }
+        self.blocking(move |_| Ok(process::find_running_executable(&game_config.executables))).await
+    }
+
+    // refuse writes into the live pc folder that the game or steam would fight over
+    async fn ensure_pc_writable(&self, game: &str, pc_save: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(executable) = self.get_running_game(game).await? {
+            return Err(format!(
+                "{} is running ({}) and will overwrite the save when it exits. Close the game first, or force the sync to write anyway.",
+                Games::get_config(game).map(|config| config.display_name).unwrap_or_default(),
+                executable
+            ).into());
+        }
 
-        let mut updated_config = config;
-        updated_config["lastSync"] = serde_json::Value::String(Utc::now().to_rfc3339());
-        self.save_config(game, updated_config).await?;
+        if let Some(cloud) = self.get_cloud_state(game, pc_save).await? {
+            if cloud.differs_from_local() {
+                return Err(format!(
+                    "Steam Cloud has a different copy of {} ({}). Let Steam finish syncing first, or force the sync to overwrite it.",
+                    cloud.file, cloud.status
+                ).into());
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn restore_backup(&self, game: &str, backup_path: &str, save_path: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut entry = SyncLogEntry::new("restore");
+        entry.game = Some(game.to_string());
+        entry.set_paths(None, backup_path, save_path);
+        self.logged(entry, self.run_restore_backup(game, backup_path, save_path, force)).await
+    }
 
-        Ok(format!("Sync completed: {}", direction))
+    async fn run_restore_backup(&self, game: &str, backup_path: &str, save_path: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if !force {
+            self.ensure_pc_writable(game, save_path).await?;
+        }
+
+        let backup_data = self.read_file(backup_path).await?;
+        // refuse to restore something the game could not load
+        save_data::decode_pc_save(&self.crypto, &backup_data)
+            .map_err(|e| format!("Backup is not a readable save: {}", e))?;
+
+        self.write_output(save_path, backup_data).await
+    }
+
+    pub async fn sync_from_backup(&self, game: &str, profile: &str, backup_path: &str) -> Result<SyncResult, Box<dyn std::error::Error>> {
+        let mut entry = SyncLogEntry::new("sync").for_profile(game, profile);
+        let result = self.run_sync_from_backup(game, profile, backup_path, &mut entry).await;
+        self.record(entry, result).await
+    }
+
+    async fn run_sync_from_backup(&self, game: &str, profile: &str, backup_path: &str, entry: &mut SyncLogEntry) -> Result<SyncResult, Box<dyn std::error::Error>> {
+        let mut config = self.load_config(game, profile).await?;
+        let target = SwitchTarget::from_config(game, &config)?;
+
+        let backup_data = self.read_file(backup_path).await?;
+        save_data::decode_pc_save(&self.crypto, &backup_data)
+            .map_err(|e| format!("Backup is not a readable save: {}", e))?;
+
+        // a restore point's own name (user1_1.5.78.11833.dat, user1.dat.bak) is one JKSV and the
+        // game never load, so it goes over as the mapped slot or else the slot it was taken from
+        let slot = self.slot_of(backup_path).ok_or_else(|| format!("{} is not a save slot or one of its restore points", backup_path))?;
+        let switch_name = config.switch_file_name().unwrap_or_else(|| format!("user{}.dat", slot));
+
+        // logged as a sync so undo_last_sync can take it back like any other
+        let written = self.written_path("pc-to-switch", &target, backup_path, Some(&switch_name));
+        entry.set_paths(Some("backup-to-switch"), backup_path, written.as_deref().unwrap_or(&config.switch_save));
+        self.capture_before(entry).await;
+
+        let source = backup_path.to_string();
+        self.write_with_undo(game, profile, written, entry, || {
+            self.blocking(move |manager| manager.sync_pc_to_switch(&source, &target, Some(&switch_name)))
+        })
+        .await?;
+
+        self.snapshot_history(game, profile, "backup-to-switch", backup_path, &config, entry).await;
+        config.last_sync = Some(Utc::now());
+        self.save_config(game, profile, &config).await?;
+
+        Ok(SyncResult {
+            direction: "backup-to-switch".to_string(),
+            message: "Sync completed: backup-to-switch".to_string(),
+            snapshot: entry.snapshot.clone(),
+            undoable: entry.undo_copy.is_some() || entry.destination_created,
+            warnings: entry.warnings.clone(),
+        })
+    }
+
+    // the slot a save or one of its restore points belongs to, from the file name alone
+    fn slot_of(&self, path: &str) -> Option<u8> {
+        let name = Path::new(path).file_name()?.to_str()?;
+        [&self.patterns.user, &self.patterns.versioned, &self.patterns.bak]
+            .iter()
+            .find_map(|pattern| pattern.captures(name)?.get(1)?.as_str().parse().ok())
+    }
+
+    pub async fn list_remote_backups(&self, game: &str, profile: &str) -> Result<Vec<webdav::RemoteBackup>, Box<dyn std::error::Error>> {
+        let config = self.load_config(game, profile).await?;
+        let target = SwitchTarget::from_config(game, &config)?;
+        self.blocking(move |_| Self::list_backups_on(target)).await
+    }
+
+    fn list_backups_on(target: SwitchTarget) -> Result<Vec<webdav::RemoteBackup>, Box<dyn std::error::Error>> {
+        match target {
+            SwitchTarget::WebDav(client) => client.list_backups(),
+            SwitchTarget::Ftp(target) => {
+                let mut session = target.connect()?;
+                let entries = session.list(&target.directory)?;
+                session.quit();
+                let mut backups = entries
+                    .into_iter()
+                    .filter(|entry| entry.is_dir || entry.name.to_lowercase().ends_with(".zip"))
+                    .map(|entry| webdav::RemoteBackup {
+                        url: ftp::join_path(&target.directory, &entry.name),
+                        modified: entry.modified.or_else(|| ftp::backup_name_time(&entry.name)),
+                        size: entry.size,
+                        name: entry.name,
+                    })
+                    .collect::<Vec<_>>();
+                backups.sort_by_key(|backup| std::cmp::Reverse(backup.modified));
+                Ok(backups)
+            }
+            SwitchTarget::Local(_) => Err("Switch save is not a remote location".into()),
+        }
+    }
+
+    async fn get_switch_info(&self, target: &SwitchTarget) -> Result<FileInfo, Box<dyn std::error::Error>> {
+        match target {
+            SwitchTarget::Local(switch_save) => self.get_file_info(switch_save).await,
+            remote => {
+                let remote = remote.clone();
+                self.blocking(move |_| Self::remote_switch_info(&remote)).await
+            }
+        }
     }
 
-    async fn sync_pc_to_switch(&self, pc_save: &str, switch_save: &str) -> Result<(), Box<dyn std::error::Error>> {
-        if is_jksv_format(switch_save) {
-            self.create_backup(pc_save, switch_save).await?;
+    fn remote_switch_info(target: &SwitchTarget) -> Result<FileInfo, Box<dyn std::error::Error>> {
+        match target {
+            SwitchTarget::Local(_) => Err("Switch save is not a remote location".into()),
+            SwitchTarget::WebDav(client) => Ok(match client.newest_backup()? {
+                Some(backup) => FileInfo {
+                    exists: true,
+                    modified: backup.modified,
+                    size: backup.size.unwrap_or(0),
+                    name: Some(backup.name),
+                },
+                None => FileInfo::default(),
+            }),
+            SwitchTarget::Ftp(target) => {
+                let mut session = target.connect()?;
+                let newest = ftp::newest_backup(session.list(&target.directory)?);
+                session.quit();
+                Ok(match newest {
+                    Some(entry) => FileInfo {
+                        exists: true,
+                        modified: entry.modified.or_else(|| ftp::backup_name_time(&entry.name)),
+                        size: entry.size.unwrap_or(0),
+                        name: Some(entry.name),
+                    },
+                    None => FileInfo::default(),
+                })
+            }
+        }
+    }
+
+    // `switch_name` is the profile's slot mapping, the file name the save gets inside a jksv backup
+    fn sync_pc_to_switch(&self, pc_save: &str, target: &SwitchTarget, switch_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let pc_name = Path::new(pc_save).file_name().and_then(|n| n.to_str()).ok_or("Invalid save file name")?;
+        let file_name = switch_name.unwrap_or(pc_name);
+
+        let switch_save = match target {
+            SwitchTarget::Local(switch_save) => switch_save.as_str(),
+            SwitchTarget::WebDav(client) => {
+                let backup = self.build_backup_zip(pc_save, file_name)?;
+                client.upload(&format!("{}.zip", Self::jksv_backup_name()), &backup)?;
+                return Ok(());
+            }
+            SwitchTarget::Ftp(target) => {
+                // same layout as create_backup: a backup folder holding the save file
+                let save_data = self.host.read(Path::new(pc_save))?;
+                let backup_dir = ftp::join_path(&target.directory, &Self::jksv_backup_name());
+
+                let mut session = target.connect()?;
+                session.make_dir(&target.directory)?;
+                session.make_dir(&backup_dir)?;
+                session.upload(&ftp::join_path(&backup_dir, file_name), &save_data)?;
+                session.quit();
+                return Ok(());
+            }
+        };
+
+        if is_jksv_format(self.host.as_ref(), switch_save) {
+            self.copy_into_backup(pc_save, switch_save, file_name)?;
         } else {
-            let pc_data = fs::read(pc_save)?;
+            let pc_data = self.host.read(Path::new(pc_save))?;
             let switch_data = self.crypto.pc_to_switch(&pc_data)?;
             
             if let Some(parent) = Path::new(switch_save).parent() {
-                fs::create_dir_all(parent)?;
+                self.host.create_dir_all(parent)?;
             }
-            fs::write(switch_save, switch_data)?;
+            self.host.write(Path::new(switch_save), switch_data.as_bytes())?;
         }
         Ok(())
     }
 
-    async fn sync_switch_to_pc(&self, switch_save: &str, pc_save: &str) -> Result<(), Box<dyn std::error::Error>> {
-        if is_jksv_format(switch_save) {
-            if Path::new(switch_save).is_dir() {
-                let save_files = self.find_save_files_in_directory(switch_save).await?;
-                if save_files.is_empty() {
-                    return Err("No save files found in backup directory".into());
+    fn sync_switch_to_pc(&self, target: &SwitchTarget, pc_save: &str, switch_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let switch_save = match target {
+            SwitchTarget::Local(switch_save) => switch_save.as_str(),
+            SwitchTarget::WebDav(client) => {
+                let backup = client.newest_backup()?.ok_or("No JKSV backups found on the WebDAV server")?;
+                let data = client.download(&backup)?;
+                return self.restore_from_backup_zip(&data, pc_save, switch_name);
+            }
+            SwitchTarget::Ftp(target) => {
+                let mut session = target.connect()?;
+                let backup = ftp::newest_backup(session.list(&target.directory)?)
+                    .ok_or("No JKSV backups found on the FTP server")?;
+                let backup_path = ftp::join_path(&target.directory, &backup.name);
+
+                if !backup.is_dir {
+                    let data = session.download(&backup_path)?;
+                    session.quit();
+                    return self.restore_from_backup_zip(&data, pc_save, switch_name);
                 }
+
+                let names: Vec<String> = session
+                    .list(&backup_path)?
+                    .into_iter()
+                    .filter(|entry| !entry.is_dir && (entry.name.ends_with(".dat") || entry.name.contains("user")))
+                    .map(|entry| entry.name)
+                    .collect();
+                let save_file = pick_save_file(&names, switch_name, "No save files found in backup directory")?;
+                let data = session.download(&ftp::join_path(&backup_path, save_file))?;
+                session.quit();
+
+                if let Some(parent) = Path::new(pc_save).parent() {
+                    self.host.create_dir_all(parent)?;
+                }
+                self.host.write(Path::new(pc_save), &data)?;
+                return Ok(());
+            }
+        };
+
+        if is_jksv_format(self.host.as_ref(), switch_save) {
+            if self.host.is_dir(Path::new(switch_save)) {
+                let save_files = self.find_save_files_in_directory(switch_save)?;
+                let save_file = pick_save_file(&save_files, switch_name, "No save files found in backup directory")?;
                 
                 if let Some(parent) = Path::new(pc_save).parent() {
-                    fs::create_dir_all(parent)?;
+                    self.host.create_dir_all(parent)?;
                 }
-                fs::copy(&save_files[0], pc_save)?;
+                self.host.copy(Path::new(save_file), Path::new(pc_save))?;
             } else {
-                let temp_dir = Path::new(pc_save).parent().unwrap().join("temp-extract");
-                fs::create_dir_all(&temp_dir)?;
+                let temp_dir = self.temp_dir(Path::new(pc_save).parent().ok_or("Invalid PC save path")?.join("temp-extract"))?;
                 
-                let extracted = self.extract_backup(switch_save, temp_dir.to_str().unwrap()).await?;
-                if extracted.is_empty() {
-                    return Err("No save files found in backup".into());
-                }
+                let extracted = self.extract_archive(switch_save, &temp_dir.path())?;
+                let save_file = pick_save_file(&extracted, switch_name, "No save files found in backup")?;
                 
-                fs::copy(&extracted[0], pc_save)?;
-                fs::remove_dir_all(&temp_dir)?;
+                self.host.copy(Path::new(save_file), Path::new(pc_save))?;
             }
         } else {
-            let switch_data = fs::read_to_string(switch_save)?;
+            let switch_data = self.host.read_to_string(Path::new(switch_save))?;
             let pc_data = self.crypto.switch_to_pc(&switch_data)?;
             
             if let Some(parent) = Path::new(pc_save).parent() {
-                fs::create_dir_all(parent)?;
+                self.host.create_dir_all(parent)?;
             }
-            fs::write(pc_save, pc_data)?;
+            self.host.write(Path::new(pc_save), &pc_data)?;
         }
         Ok(())
     }
 
-    async fn find_save_files_in_directory(&self, directory: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    fn find_save_files_in_directory(&self, directory: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let mut save_files = Vec::new();
-        let entries = fs::read_dir(directory)?;
+        let entries = self.host.read_dir(Path::new(directory))?;
         
-        for entry in entries.flatten() {
-            if let Some(file_name) = entry.file_name().to_str() {
+        for entry in entries {
+            if let Some(file_name) = entry.file_name().and_then(|n| n.to_str()) {
                 if file_name.ends_with(".dat") || file_name.contains("user") {
-                    save_files.push(entry.path().to_string_lossy().to_string());
+                    save_files.push(entry.to_string_lossy().to_string());
                 }
             }
         }
@@ -359,47 +1286,319 @@ impl SaveManager {
         Ok(save_files)
     }
 
+    // raw save bytes from a pc .dat, switch json, jksv backup folder or jksv zip
+    pub async fn read_save_bytes(&self, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let path = path.to_string();
+        self.blocking(move |manager| manager.read_save_file(&path)).await
+    }
+
+    fn read_save_file(&self, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let file_path = Path::new(path);
+
+        if self.host.is_dir(file_path) {
+            let save_files = self.find_save_files_in_directory(path)?;
+            let save_file = save_files.first().ok_or("No save files found in backup directory")?;
+            return Ok(self.host.read(Path::new(save_file))?);
+        }
+
+        let is_zip = file_path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("zip"));
+        if is_zip {
+            let mut archive = ZipArchive::new(Cursor::new(self.host.read(file_path)?))?;
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i)?;
+                if self.should_extract_file(&file) {
+                    let mut content = Vec::new();
+                    file.read_to_end(&mut content)?;
+                    return Ok(content);
+                }
+            }
+            return Err("No save files found in backup".into());
+        }
+
+        Ok(self.host.read(file_path)?)
+    }
+
+    pub async fn load_save(&self, path: &str) -> Result<Value, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(&self.sniffed_json(path).await?)?)
+    }
+
+    pub async fn diff_saves(&self, left_path: &str, right_path: &str) -> Result<SaveDiff, Box<dyn std::error::Error>> {
+        let left = self.load_save(left_path).await?;
+        let right = self.load_save(right_path).await?;
+        Ok(save_diff::diff_saves(&left, &right))
+    }
+
+    pub async fn merge_saves(
+        &self,
+        base_path: &str,
+        other_path: &str,
+        categories: &[String],
+        output_path: &str,
+        force: bool,
+    ) -> Result<MergeResult, Box<dyn std::error::Error>> {
+        let base = self.load_save(base_path).await?;
+        let other = self.load_save(other_path).await?;
+        let (merged, taken_fields) = save_merge::merge_saves(&base, &other, categories)?;
+        let findings = save_validate::check_consistency(&merged);
+
+        if findings.iter().any(|finding| finding.is_error()) && !force {
+            return Ok(MergeResult {
+                output: None,
+                taken_fields,
+                findings,
+                written: false,
+            });
+        }
+
+        let json = serde_json::to_string(&merged)?;
+        let is_json = Path::new(output_path).extension().map_or(false, |ext| ext.eq_ignore_ascii_case("json"));
+        let output_data = if is_json {
+            serde_json::to_string_pretty(&merged)?.into_bytes()
+        } else {
+            self.crypto.switch_to_pc(&json)?
+        };
+
+        self.write_output(output_path, output_data).await?;
+
+        Ok(MergeResult {
+            output: Some(output_path.to_string()),
+            taken_fields,
+            findings,
+            written: true,
+        })
+    }
+
+    // applies the edits in order and writes the save back in the variant it was read as,
+    // refusing results the game can't load
+    pub async fn edit_save(&self, input_path: &str, output_path: &str, edits: &[SaveEdit]) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+        self.ensure_not_into_container(input_path, output_path)?;
+        let sniffed = self.sniffed(input_path).await?;
+        let mut editor = SaveEditor::new(serde_json::from_str(&sniffed.json)?)?;
+        for edit in edits {
+            editor.apply(edit)?;
+        }
+
+        let edited = editor.into_value();
+        let findings = save_validate::check_consistency(&edited);
+        if let Some(error) = findings.iter().find(|finding| finding.is_error()) {
+            return Err(format!("Edit would leave the save inconsistent: {}", error.message).into());
+        }
+
+        let output = save_format::encode(&self.crypto, &serde_json::to_string(&edited)?, sniffed.detected.variant)?;
+        self.write_output(output_path, output).await?;
+        Ok(findings)
+    }
+
+    pub async fn validate_save(&self, path: &str) -> Result<ValidationReport, Box<dyn std::error::Error>> {
+        let data = self.read_save_bytes(path).await?;
+        let (report, _) = save_validate::inspect(&self.crypto, &data);
+        Ok(report)
+    }
+
+    // writes the recovered save back in the format it was read in; only the repairs
+    // listed in the report are applied, anything else is left for the user to fix.
+    // Nothing is written when there was nothing to repair
+    pub async fn repair_save(&self, input_path: &str, output_path: &str) -> Result<ValidationReport, Box<dyn std::error::Error>> {
+        self.ensure_not_into_container(input_path, output_path)?;
+        let data = self.read_save_bytes(input_path).await?;
+        let (mut report, save) = save_validate::inspect(&self.crypto, &data);
+        let save = save.ok_or("Save could not be decoded, nothing to repair")?;
+        if report.repairs.is_empty() {
+            return Ok(report);
+        }
+
+        let json = serde_json::to_string(&save)?;
+        let output = if report.format == "switch-json" { json.into_bytes() } else { self.crypto.switch_to_pc(&json)? };
+        self.write_output(output_path, output).await?;
+
+        // what's left after repair is what the caller still needs to look at
+        report.findings = save_validate::check_structure(&save);
+        report.findings.extend(save_validate::check_consistency(&save));
+        Ok(report)
+    }
+
+    // both directions accept anything sniff_save recognises, so web editor downloads and
+    // JKSV backups convert like native saves
     pub async fn pc_to_switch(&self, input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let pc_data = fs::read(input_path)?;
-        let switch_data = self.crypto.pc_to_switch(&pc_data)?;
+        let switch_data = self.sniffed_json(input_path).await?;
         
-        if let Some(parent) = Path::new(output_path).parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::write(output_path, switch_data)?;
+        self.write_output(output_path, switch_data).await?;
         Ok(())
     }
 
     pub async fn switch_to_pc(&self, input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let switch_data = fs::read_to_string(input_path)?;
-        let pc_data = self.crypto.switch_to_pc(&switch_data)?;
+        let switch_data = self.sniffed_json(input_path).await?;
+        self.write_pc_save(output_path, &switch_data).await
+    }
+
+    async fn write_pc_save(&self, output_path: &str, switch_data: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let pc_data = self.crypto.switch_to_pc(switch_data)?;
         
-        if let Some(parent) = Path::new(output_path).parent() {
-            fs::create_dir_all(parent)?;
+        self.write_output(output_path, pc_data).await?;
+        Ok(())
+    }
+
+    pub fn sniff_save(&self, path: &str) -> Result<SniffedSave, Box<dyn std::error::Error>> {
+        save_format::sniff(&self.crypto, self.host.as_ref(), path)
+    }
+
+    pub async fn sniffed(&self, path: &str) -> Result<SniffedSave, Box<dyn std::error::Error>> {
+        let path = path.to_string();
+        self.blocking(move |manager| manager.sniff_save(&path)).await
+    }
+
+    async fn sniffed_json(&self, path: &str) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(self.sniffed(path).await?.json)
+    }
+
+    // jksv zips and folders hold a save rather than being one, so edits can't be written back into them
+    pub fn is_backup_container(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        self.host.is_dir(path) || path.extension().map_or(false, |ext| ext.eq_ignore_ascii_case("zip"))
+    }
+
+    fn ensure_not_into_container(&self, input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        if Path::new(input_path) == Path::new(output_path) && self.is_backup_container(input_path) {
+            return Err(format!("{} is a backup zip or folder, pick a separate file to write the save to", input_path).into());
         }
-        fs::write(output_path, pc_data)?;
         Ok(())
     }
 
+    // a file next to `input_path` that doesn't exist yet: user1.edited.dat, then user1.edited-2.dat
+    pub fn sibling_output(&self, input_path: &str, tag: &str) -> String {
+        let input = Path::new(input_path);
+        let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("save");
+        let extension = input.extension().and_then(|e| e.to_str()).map(|e| format!(".{}", e)).unwrap_or_default();
+        (1..)
+            .map(|n| if n == 1 { format!("{}.{}{}", stem, tag, extension) } else { format!("{}.{}-{}{}", stem, tag, n, extension) })
+            .map(|name| input.with_file_name(name))
+            .find(|candidate| !self.host.exists(candidate))
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+
+    pub async fn detect_save_variant(&self, path: &str) -> Result<SaveVariant, Box<dyn std::error::Error>> {
+        let path = path.to_string();
+        self.blocking(move |manager| Ok(manager.sniff_save(&path)?.detected.variant)).await
+    }
+
+    pub async fn export_web_editor(&self, input_path: &str, output_path: &str, variant: SaveVariant) -> Result<(), Box<dyn std::error::Error>> {
+        if !save_format::WEB_EDITOR_VARIANTS.contains(&variant) {
+            return Err(format!("The web editor can't open a {}", variant.label()).into());
+        }
+
+        let json = self.sniffed_json(input_path).await?;
+        let output = save_format::encode(&self.crypto, &json, variant)?;
+        self.write_output(output_path, output).await?;
+        Ok(())
+    }
+
+    // reads either platform's save and writes it as plain json for hand editing
+    pub async fn export_json(&self, input_path: &str, output_path: &str, minify: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let save = self.load_save(input_path).await?;
+        self.write_json(output_path, &save, minify).await
+    }
+
+    async fn write_json(&self, output_path: &str, save: &Value, minify: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let mut json = if minify { serde_json::to_string(&save)? } else { serde_json::to_string_pretty(&save)? };
+        json.push('\n');
+
+        self.write_output(output_path, json).await?;
+        Ok(())
+    }
+
+    // `target` is "pc" for the encrypted save or "switch" for the JKSV json file
+    pub async fn import_json(&self, input_path: &str, output_path: &str, target: &str) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+        let text = String::from_utf8(self.read_file(input_path).await?)?;
+        self.import_text(input_path, &text, output_path, target).await
+    }
+
+    async fn import_text(&self, input_path: &str, text: &str, output_path: &str, target: &str) -> Result<Vec<Finding>, Box<dyn std::error::Error>> {
+        let save: Value = serde_json::from_str(text.trim_start_matches('\u{feff}'))
+            .map_err(|e| format!("{} is not valid JSON: {}", input_path, e))?;
+
+        let findings = save_validate::check_schema(&save);
+        let errors: Vec<&str> = findings.iter().filter(|f| f.is_error()).map(|f| f.message.as_str()).collect();
+        if !errors.is_empty() {
+            return Err(format!("JSON does not look like a save: {}", errors.join("; ")).into());
+        }
+
+        let json = serde_json::to_string(&save)?;
+        let output = match target {
+            "pc" => self.crypto.switch_to_pc(&json)?,
+            "switch" => json.into_bytes(),
+            other => return Err(format!("Unknown import target: {}", other).into()),
+        };
+        self.write_output(output_path, output).await?;
+        Ok(findings)
+    }
+
+    // `input` is the caller's sniff of `input_path`, which `direction` has already been checked
+    // against, so the save is decoded only once
+    pub async fn convert(
+        &self,
+        input_path: &str,
+        input: SniffedSave,
+        output_path: &str,
+        direction: &str,
+        minify: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut entry = SyncLogEntry::new("convert");
+        entry.set_paths(Some(direction), input_path, output_path);
+        (entry.source_sha, entry.source_summary) = Self::sniffed_state(&input);
+        self.logged(entry, async {
+            match direction {
+                "pc-to-switch" => self.write_output(output_path, input.json).await,
+                "switch-to-pc" => self.write_pc_save(output_path, &input.json).await,
+                "to-json" => self.write_json(output_path, &serde_json::from_str(&input.json)?, minify).await,
+                "json-to-pc" => self.import_text(input_path, &input.json, output_path, "pc").await.map(|_| ()),
+                "json-to-switch" => self.import_text(input_path, &input.json, output_path, "switch").await.map(|_| ()),
+                other => Err(format!("Unknown conversion direction: {}", other).into()),
+            }
+        })
+        .await
+    }
+
     pub async fn create_backup(&self, save_file: &str, output_dir: &str) -> Result<(), Box<dyn std::error::Error>> {
-        fs::create_dir_all(output_dir)?;
-        
-        let save_data = fs::read(save_file)?;
         let file_name = Path::new(save_file).file_name().unwrap().to_str().unwrap();
+        let mut entry = SyncLogEntry::new("backup");
+        entry.set_paths(None, save_file, &Path::new(output_dir).join(file_name).to_string_lossy());
+        let (source, folder, name) = (save_file.to_string(), output_dir.to_string(), file_name.to_string());
+        self.logged(entry, self.blocking(move |manager| manager.copy_into_backup(&source, &folder, &name))).await
+    }
+
+    fn copy_into_backup(&self, save_file: &str, output_dir: &str, file_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.host.create_dir_all(Path::new(output_dir))?;
+        
+        let save_data = self.host.read(Path::new(save_file))?;
         let backup_file_path = Path::new(output_dir).join(file_name);
         
-        fs::write(&backup_file_path, &save_data)?;
+        self.host.write(&backup_file_path, &save_data)?;
 
         Ok(())
     }
 
     pub async fn extract_backup(&self, backup_file: &str, output_dir: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let file = File::open(backup_file)?;
-        let mut archive = ZipArchive::new(file)?;
+        let (backup_file, output_dir) = (backup_file.to_string(), output_dir.to_string());
+        self.blocking(move |manager| manager.extract_archive(&backup_file, &output_dir)).await
+    }
+
+    // a cancelled or failed extraction removes whatever it already wrote
+    fn extract_archive(&self, backup_file: &str, output_dir: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let mut archive = ZipArchive::new(Cursor::new(self.host.read(Path::new(backup_file))?))?;
         let mut extracted_files = Vec::new();
-        
-        fs::create_dir_all(output_dir)?;
-        
+
+        let mut bytes_total = 0;
+        for i in 0..archive.len() {
+            let file = archive.by_index(i)?;
+            if self.should_extract_file(&file) {
+                bytes_total += file.size();
+            }
+        }
+
+        self.host.create_dir_all(Path::new(output_dir))?;
+
+        let mut bytes_done = 0;
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)?;
             
@@ -407,14 +1606,70 @@ impl SaveManager {
                 continue;
             }
             
-            if let Some(output_path) = self.extract_archive_file(&mut file, output_dir)? {
-                extracted_files.push(output_path);
+            match self.extract_archive_file(&mut file, output_dir, &mut bytes_done, bytes_total) {
+                Ok(Some(output_path)) => extracted_files.push(output_path),
+                Ok(None) => {}
+                Err(e) => {
+                    for extracted in &extracted_files {
+                        let _ = self.host.remove_file(Path::new(extracted));
+                    }
+                    return Err(e);
+                }
             }
         }
         
         Ok(extracted_files)
     }
 
+    fn temp_dir(&self, path: PathBuf) -> std::io::Result<TempDir> {
+        self.host.create_dir_all(&path)?;
+        Ok(TempDir { host: self.host.clone(), path })
+    }
+
+    async fn read_file(&self, path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let path = path.to_string();
+        self.blocking(move |manager| Ok(manager.host.read(Path::new(&path))?)).await
+    }
+
+    // every command that writes a save lets the user pick the path, so its folder may not exist yet
+    async fn write_output(&self, path: &str, data: impl Into<Vec<u8>>) -> Result<(), Box<dyn std::error::Error>> {
+        let (path, data) = (PathBuf::from(path), data.into());
+        self.blocking(move |manager| {
+            if let Some(parent) = path.parent() {
+                manager.host.create_dir_all(parent)?;
+            }
+            Ok(manager.host.write(&path, &data)?)
+        })
+        .await
+    }
+
+    // same name jksv gives its own backups, so they sort alongside them on the switch
+    pub fn jksv_backup_name() -> String {
+        format!("HollowSync - {}", chrono::Local::now().format("%Y.%m.%d @ %H.%M.%S"))
+    }
+
+    // zip with the same layout create_backup writes into a jksv folder
+    pub fn build_backup_zip(&self, save_file: &str, file_name: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let save_data = self.host.read(Path::new(save_file))?;
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer.start_file(file_name, zip::write::FileOptions::default())?;
+        writer.write_all(&save_data)?;
+        Ok(writer.finish()?.into_inner())
+    }
+
+    fn restore_from_backup_zip(&self, data: &[u8], pc_save: &str, switch_name: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let parent = Path::new(pc_save).parent().ok_or("Invalid PC save path")?;
+        let temp_dir = self.temp_dir(parent.join("temp-extract"))?;
+
+        let temp_zip = Path::new(&temp_dir.path()).join("download.zip");
+        self.host.write(&temp_zip, data)?;
+        let extracted = self.extract_archive(&temp_zip.to_string_lossy(), &temp_dir.path())?;
+        let save_file = pick_save_file(&extracted, switch_name, "No save files found in backup")?;
+        self.host.copy(Path::new(save_file), Path::new(pc_save))?;
+        Ok(())
+    }
+
     fn should_extract_file(&self, file: &zip::read::ZipFile) -> bool {
         !file.is_dir() && (file.name().contains("user") || file.name().ends_with(".dat"))
     }
@@ -423,17 +1678,64 @@ impl SaveManager {
         &self,
         file: &mut zip::read::ZipFile,
         output_dir: &str,
+        bytes_done: &mut u64,
+        bytes_total: u64,
     ) -> Result<Option<String>, Box<dyn std::error::Error>> {
-        let Some(file_name) = Path::new(file.name()).file_name().and_then(|n| n.to_str()) else {
+        let Some(file_name) = Path::new(file.name()).file_name().and_then(|n| n.to_str()).map(|n| n.to_string()) else {
             return Ok(None);
         };
         
-        let output_path = Path::new(output_dir).join(file_name);
-        
-        let mut content = Vec::new();
-        file.read_to_end(&mut content)?;
-        
-        fs::write(&output_path, content)?;
+        let output_path = Path::new(output_dir).join(&file_name);
+        let mut output = Vec::new();
+
+        // read in chunks so a large entry still reports progress and can be cancelled
+        let mut buffer = vec![0; 64 * 1024];
+        let copied = loop {
+            if let Err(e) = self.progress.check() {
+                break Err(e);
+            }
+            let read = match file.read(&mut buffer) {
+                Ok(0) => break Ok(()),
+                Ok(read) => read,
+                Err(e) => break Err(e.into()),
+            };
+            output.extend_from_slice(&buffer[..read]);
+            *bytes_done += read as u64;
+            self.progress.report("extracting", Some(&file_name), *bytes_done, Some(bytes_total), progress::percent(*bytes_done, bytes_total));
+        };
+
+        copied?;
+        self.host.write(&output_path, &output)?;
         Ok(Some(output_path.to_string_lossy().to_string()))
     }
-}
\ No newline at end of file
+}
+
+// a scratch folder that is removed however the operation using it ends, cancellation included
+struct TempDir {
+    host: Arc<dyn Host>,
+    path: PathBuf,
+}
+
+impl TempDir {
+    fn path(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = self.host.remove_dir_all(&self.path);
+    }
+}
+
+// the profile's mapped slot when it has one, which the backup must then hold; any other
+// slot would overwrite the pc save with a different playthrough
+fn pick_save_file<'a>(files: &'a [String], switch_name: Option<&str>, none_found: &str) -> Result<&'a String, String> {
+    match switch_name {
+        Some(name) => files
+            .iter()
+            .find(|file| Path::new(file).file_name().and_then(|n| n.to_str()) == Some(name))
+            .ok_or_else(|| format!("The backup has no {}, the save for the profile's Switch slot", name)),
+        None => files.first().ok_or_else(|| none_found.to_string()),
+    }
+}
@@ -0,0 +1,43 @@
+use crate::save_diff::{categorize, CATEGORIES};
+use crate::save_validate::Finding;
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct MergeResult {
+    pub output: Option<String>,
+    pub taken_fields: Vec<String>,
+    pub findings: Vec<Finding>,
+    pub written: bool,
+}
+
+// starts from `base` and copies every playerData field of the chosen categories from `other`;
+// sceneData (broken walls, opened doors) always comes from the base save
+pub fn merge_saves(base: &Value, other: &Value, categories: &[String]) -> Result<(Value, Vec<String>), Box<dyn std::error::Error>> {
+    for category in categories {
+        if !CATEGORIES.contains(&category.as_str()) {
+            return Err(format!("Unknown merge category: {}", category).into());
+        }
+    }
+
+    let mut merged = base.clone();
+    let other_player = other["playerData"].as_object().ok_or("Other save has no playerData")?;
+    let merged_player = merged["playerData"].as_object_mut().ok_or("Base save has no playerData")?;
+
+    let mut taken = Vec::new();
+    for (field, value) in other_player {
+        if !categories.iter().any(|category| category == categorize(field)) {
+            continue;
+        }
+        if merged_player.get(field) != Some(value) {
+            merged_player.insert(field.clone(), value.clone());
+            taken.push(field.clone());
+        }
+    }
+    taken.sort();
+
+    Ok((merged, taken))
+}
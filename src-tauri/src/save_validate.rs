@@ -0,0 +1,530 @@
+use crate::crypto::SaveCrypto;
+use base64::{engine::general_purpose, Engine as _};
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct Finding {
+    pub severity: String,
+    pub code: String,
+    pub field: Option<String>,
+    pub message: String,
+    pub fixable: bool,
+}
+
+impl Finding {
+    fn new(severity: &str, code: &str, field: Option<&str>, message: String) -> Self {
+        Self {
+            severity: severity.to_string(),
+            code: code.to_string(),
+            field: field.map(|f| f.to_string()),
+            message,
+            fixable: false,
+        }
+    }
+
+    fn fixable(mut self) -> Self {
+        self.fixable = true;
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == "error"
+    }
+}
+
+fn charm_numbers(player: &serde_json::Map<String, Value>, prefix: &str) -> Vec<u32> {
+    player
+        .iter()
+        .filter(|(_, value)| value.as_bool() == Some(true))
+        .filter_map(|(key, _)| key.strip_prefix(prefix)?.parse::<u32>().ok())
+        .collect()
+}
+
+// cross-field rules the game relies on; breaking them tends to soft-lock or crash a load
+pub fn check_consistency(save: &Value) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Some(player) = save["playerData"].as_object() else {
+        findings.push(Finding::new("error", "missing-player-data", Some("playerData"), "Save has no playerData object".to_string()));
+        return findings;
+    };
+
+    let owned = charm_numbers(player, "gotCharm_");
+    let equipped = charm_numbers(player, "equippedCharm_");
+    for charm in &equipped {
+        if !owned.contains(charm) {
+            findings.push(Finding::new(
+                "error",
+                "equipped-charm-not-owned",
+                Some(&format!("equippedCharm_{}", charm)),
+                format!("Charm {} is equipped but not owned", charm),
+            ).fixable());
+        }
+    }
+
+    if let Some(slots) = player.get("charmSlots").and_then(|v| v.as_i64()) {
+        let used: i64 = equipped
+            .iter()
+            .filter_map(|charm| player.get(&format!("charmCost_{}", charm)).and_then(|v| v.as_i64()))
+            .sum();
+        let overcharmed = player.get("overcharmed").and_then(|v| v.as_bool()).unwrap_or(false);
+        if used > slots && !overcharmed {
+            findings.push(Finding::new(
+                "warning",
+                "charm-notches-exceeded",
+                Some("charmSlots"),
+                format!("Equipped charms cost {} notches but only {} are available", used, slots),
+            ));
+        }
+    }
+
+    for (field, limit) in [("heartPieces", 4), ("vesselFragments", 3)] {
+        if let Some(value) = player.get(field).and_then(|v| v.as_i64()) {
+            if !(0..limit).contains(&value) {
+                findings.push(Finding::new(
+                    "warning",
+                    "fragment-count-out-of-range",
+                    Some(field),
+                    format!("{} is {}, expected 0 to {}", field, value, limit - 1),
+                ).fixable());
+            }
+        }
+    }
+
+    for field in ["geo", "geoPool", "ore", "simpleKeys", "rancidEggs", "dreamOrbs"] {
+        if let Some(value) = player.get(field).and_then(|v| v.as_i64()) {
+            if value < 0 {
+                findings.push(Finding::new("error", "negative-currency", Some(field), format!("{} is negative ({})", field, value)).fixable());
+            }
+        }
+    }
+
+    if let (Some(max_health), Some(health)) = (
+        player.get("maxHealth").and_then(|v| v.as_i64()),
+        player.get("health").and_then(|v| v.as_i64()),
+    ) {
+        if health > max_health {
+            findings.push(Finding::new(
+                "warning",
+                "health-above-max",
+                Some("health"),
+                format!("health {} is above maxHealth {}", health, max_health),
+            ).fixable());
+        }
+    }
+
+    if let (Some(scene), Some(visited)) = (
+        player.get("respawnScene").and_then(|v| v.as_str()),
+        player.get("scenesVisited").and_then(|v| v.as_array()),
+    ) {
+        if !scene.is_empty() && !visited.iter().any(|v| v.as_str() == Some(scene)) {
+            findings.push(Finding::new(
+                "warning",
+                "respawn-scene-not-visited",
+                Some("respawnScene"),
+                format!("Respawn scene {} is not in scenesVisited", scene),
+            ));
+        }
+    }
+
+    findings
+}
+
+const REQUIRED_PLAYER_FIELDS: [&str; 6] = ["version", "playTime", "geo", "maxHealth", "health", "respawnScene"];
+
+// (field, min, max) for numbers the game indexes or divides with
+const NUMERIC_RANGES: [(&str, f64, f64); 7] = [
+    ("maxHealth", 1.0, 12.0),
+    ("maxHealthBase", 1.0, 12.0),
+    ("MPReserveMax", 0.0, 99.0),
+    ("charmSlots", 0.0, 11.0),
+    ("nailSmithUpgrades", 0.0, 4.0),
+    ("playTime", 0.0, f64::MAX),
+    ("completionPercentage", 0.0, 112.0),
+];
+
+// boss kill flags that must agree with the hunter's journal
+const BOSS_FLAGS: [(&str, &str); 6] = [
+    ("falseKnightDefeated", "killedFalseKnight"),
+    ("hornet1Defeated", "killedHornet"),
+    ("mageLordDefeated", "killedMageLord"),
+    ("defeatedMantisLords", "killedMantisLord"),
+    ("defeatedDungDefender", "killedDungDefender"),
+    ("defeatedMegaJelly", "killedMegaJellyfish"),
+];
+
+// doors whose opened flag requires the matching key to have been obtained
+const DOOR_FLAGS: [(&str, &str); 3] = [
+    ("openedCityGate", "hasCityKey"),
+    ("openedWaterwaysManhole", "hasSimpleKey"),
+    ("openedLoveDoor", "hasLoveKey"),
+];
+
+// types the game's deserializer insists on; hand edits most often break these by quoting numbers
+const FIELD_TYPES: [(&str, &str); 13] = [
+    ("version", "string"),
+    ("playTime", "number"),
+    ("geo", "integer"),
+    ("health", "integer"),
+    ("maxHealth", "integer"),
+    ("maxHealthBase", "integer"),
+    ("MPReserveMax", "integer"),
+    ("charmSlots", "integer"),
+    ("permadeathMode", "integer"),
+    ("respawnScene", "string"),
+    ("respawnMarkerName", "string"),
+    ("scenesVisited", "array"),
+    ("equippedCharms", "array"),
+];
+
+fn expected_type(field: &str) -> Option<&'static str> {
+    if let Some((_, kind)) = FIELD_TYPES.iter().find(|(name, _)| *name == field) {
+        return Some(kind);
+    }
+    if field.starts_with("gotCharm_") || field.starts_with("equippedCharm_") || field.starts_with("has") {
+        return Some("boolean");
+    }
+    if field.starts_with("charmCost_") {
+        return Some("integer");
+    }
+    None
+}
+
+fn has_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        _ => true,
+    }
+}
+
+// checks an imported document has the shape of a save before it is encoded for either platform
+pub fn check_schema(save: &Value) -> Vec<Finding> {
+    if !save.is_object() {
+        return vec![Finding::new("error", "not-an-object", None, "Save JSON must be an object".to_string())];
+    }
+
+    let mut findings = check_structure(save);
+    if let Some(player) = save["playerData"].as_object() {
+        for (field, value) in player {
+            if let Some(kind) = expected_type(field) {
+                if !has_type(value, kind) {
+                    findings.push(Finding::new("error", "wrong-type", Some(field), format!("{} should be a {}, found {}", field, kind, value)));
+                }
+            }
+        }
+    }
+    findings
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct ValidationReport {
+    pub format: String,
+    pub decoded: bool,
+    pub findings: Vec<Finding>,
+    pub repairs: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.decoded && !self.findings.iter().any(|finding| finding.is_error())
+    }
+}
+
+pub fn check_structure(save: &Value) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    for key in ["playerData", "sceneData"] {
+        if !save[key].is_object() {
+            findings.push(Finding::new("error", "missing-top-level-key", Some(key), format!("Save is missing {}", key)));
+        }
+    }
+
+    let Some(player) = save["playerData"].as_object() else {
+        return findings;
+    };
+
+    for field in REQUIRED_PLAYER_FIELDS {
+        if !player.contains_key(field) {
+            findings.push(Finding::new("warning", "missing-field", Some(field), format!("playerData is missing {}", field)));
+        }
+    }
+
+    for (field, min, max) in NUMERIC_RANGES {
+        if let Some(value) = player.get(field).and_then(|v| v.as_f64()) {
+            if value < min || value > max {
+                findings.push(Finding::new("warning", "value-out-of-range", Some(field), format!("{} is {}, outside {}..{}", field, value, min, max)));
+            }
+        }
+    }
+
+    for (defeated, journal) in BOSS_FLAGS {
+        let defeated_value = player.get(defeated).and_then(|v| v.as_bool());
+        let journal_value = player.get(journal).and_then(|v| v.as_bool());
+        if defeated_value == Some(true) && journal_value == Some(false) {
+            findings.push(Finding::new("warning", "boss-flag-mismatch", Some(journal), format!("{} is set but the journal has no {}", defeated, journal)).fixable());
+        }
+    }
+
+    for (opened, key) in DOOR_FLAGS {
+        if player.get(opened).and_then(|v| v.as_bool()) == Some(true) && player.get(key).and_then(|v| v.as_bool()) == Some(false) {
+            let used_key = format!("used{}", key.trim_start_matches("has"));
+            if player.get(&used_key).and_then(|v| v.as_bool()) != Some(true) {
+                findings.push(Finding::new("info", "door-without-key", Some(opened), format!("{} is set without {}", opened, key)));
+            }
+        }
+    }
+
+    // persistent objects in scenes the player has never been to are usually leftovers from editing
+    if let (Some(visited), Some(items)) = (
+        player.get("scenesVisited").and_then(|v| v.as_array()),
+        save["sceneData"]["persistentBoolItems"].as_array(),
+    ) {
+        let unknown = items
+            .iter()
+            .filter_map(|item| item["sceneName"].as_str())
+            .filter(|scene| !visited.iter().any(|v| v.as_str() == Some(scene)))
+            .count();
+        if unknown > 0 {
+            findings.push(Finding::new("info", "unvisited-scene-reference", Some("sceneData"), format!("{} scene objects belong to scenes not in scenesVisited", unknown)));
+        }
+    }
+
+    findings
+}
+
+// fixes the cross-field problems check_consistency marks as fixable
+fn repair_values(save: &mut Value, repairs: &mut Vec<String>) {
+    let Some(player) = save["playerData"].as_object_mut() else {
+        return;
+    };
+
+    let owned = charm_numbers(player, "gotCharm_");
+    for charm in charm_numbers(player, "equippedCharm_") {
+        if !owned.contains(&charm) {
+            player.insert(format!("equippedCharm_{}", charm), false.into());
+            if let Some(equipped) = player.get_mut("equippedCharms").and_then(|v| v.as_array_mut()) {
+                equipped.retain(|id| id.as_u64() != Some(charm as u64));
+            }
+            repairs.push(format!("Unequipped charm {} which was not owned", charm));
+        }
+    }
+
+    for field in ["geo", "geoPool", "ore", "simpleKeys", "rancidEggs", "dreamOrbs"] {
+        if player.get(field).and_then(|v| v.as_i64()).map_or(false, |v| v < 0) {
+            player.insert(field.to_string(), 0.into());
+            repairs.push(format!("Reset negative {} to 0", field));
+        }
+    }
+
+    for (field, limit) in [("heartPieces", 4), ("vesselFragments", 3)] {
+        if let Some(value) = player.get(field).and_then(|v| v.as_i64()) {
+            let clamped = value.clamp(0, limit - 1);
+            if clamped != value {
+                player.insert(field.to_string(), clamped.into());
+                repairs.push(format!("Clamped {} from {} to {}", field, value, clamped));
+            }
+        }
+    }
+
+    if let (Some(max_health), Some(health)) = (
+        player.get("maxHealth").and_then(|v| v.as_i64()),
+        player.get("health").and_then(|v| v.as_i64()),
+    ) {
+        if health > max_health {
+            player.insert("health".to_string(), max_health.into());
+            repairs.push(format!("Lowered health from {} to maxHealth {}", health, max_health));
+        }
+    }
+
+    for (defeated, journal) in BOSS_FLAGS {
+        if player.get(defeated).and_then(|v| v.as_bool()) == Some(true) && player.get(journal).and_then(|v| v.as_bool()) == Some(false) {
+            player.insert(journal.to_string(), true.into());
+            repairs.push(format!("Set {} to match {}", journal, defeated));
+        }
+    }
+}
+
+// the index just past the first balanced top-level value, or the stack of still-open
+// brackets at the last point the text could be cut cleanly
+fn scan_json(text: &str) -> Result<usize, (usize, Vec<char>)> {
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut safe_cut = (0, Vec::new());
+
+    for (index, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                stack.push(c);
+                safe_cut = (index + 1, stack.clone());
+            }
+            '}' | ']' => {
+                stack.pop();
+                if stack.is_empty() {
+                    return Ok(index + 1);
+                }
+                safe_cut = (index + 1, stack.clone());
+            }
+            ',' => safe_cut = (index, stack.clone()),
+            _ => {}
+        }
+    }
+
+    Err(safe_cut)
+}
+
+fn repair_json_text(text: &str, repairs: &mut Vec<String>) -> Option<Value> {
+    let trimmed = text.trim_start_matches('\u{feff}').trim_end_matches(|c: char| c == '\0' || c.is_whitespace());
+    if let Ok(value) = serde_json::from_str(trimmed) {
+        return Some(value);
+    }
+
+    match scan_json(trimmed) {
+        Ok(end) => {
+            let value = serde_json::from_str(&trimmed[..end]).ok()?;
+            repairs.push(format!("Removed {} bytes of trailing data after the JSON", trimmed.len() - end));
+            Some(value)
+        }
+        Err((cut, open)) if cut > 0 => {
+            let mut closed = trimmed[..cut].trim_end().trim_end_matches(',').to_string();
+            for bracket in open.iter().rev() {
+                closed.push(if *bracket == '{' { '}' } else { ']' });
+            }
+            let value = serde_json::from_str(&closed).ok()?;
+            repairs.push(format!("Closed truncated JSON, dropping {} bytes of partial data", trimmed.len() - cut));
+            Some(value)
+        }
+        Err(_) => None,
+    }
+}
+
+// walks the same steps as SaveCrypto::pc_to_switch but keeps going past recoverable damage,
+// recording a finding for each problem and the repair that got past it
+pub fn inspect(crypto: &SaveCrypto, data: &[u8]) -> (ValidationReport, Option<Value>) {
+    let mut findings = Vec::new();
+    let mut repairs = Vec::new();
+
+    let first = data.iter().position(|b| !b.is_ascii_whitespace()).map(|i| data[i]);
+    let is_json = first == Some(b'{') || data.starts_with(&[0xEF, 0xBB, 0xBF]);
+    let format = if is_json { "switch-json" } else { "pc" };
+
+    let json_bytes = if is_json {
+        data.to_vec()
+    } else {
+        match decode_pc_layers(crypto, data, &mut findings, &mut repairs) {
+            Some(bytes) => bytes,
+            None => {
+                let report = ValidationReport { format: format.to_string(), decoded: false, findings, repairs };
+                return (report, None);
+            }
+        }
+    };
+
+    let text = String::from_utf8_lossy(&json_bytes);
+    let repairs_before = repairs.len();
+    let Some(mut save) = repair_json_text(&text, &mut repairs) else {
+        findings.push(Finding::new("error", "invalid-json", None, "Decoded data is not valid JSON and could not be recovered".to_string()));
+        let report = ValidationReport { format: format.to_string(), decoded: false, findings, repairs };
+        return (report, None);
+    };
+    if repairs.len() > repairs_before {
+        findings.push(Finding::new("error", "damaged-json", None, "Save JSON is truncated or has trailing data".to_string()).fixable());
+    }
+
+    findings.extend(check_structure(&save));
+    findings.extend(check_consistency(&save));
+    repair_values(&mut save, &mut repairs);
+
+    let report = ValidationReport { format: format.to_string(), decoded: true, findings, repairs };
+    (report, Some(save))
+}
+
+fn decode_pc_layers(crypto: &SaveCrypto, data: &[u8], findings: &mut Vec<Finding>, repairs: &mut Vec<String>) -> Option<Vec<u8>> {
+    if !SaveCrypto::has_save_header(data) {
+        findings.push(Finding::new("error", "missing-header", None, "File does not start with the BinaryFormatter header".to_string()));
+        return None;
+    }
+
+    // remove_save_header drops the final byte, which must be the 0x0B record terminator
+    let mut framed = data.to_vec();
+    if framed.last() != Some(&11) {
+        framed.push(11);
+        findings.push(Finding::new("error", "missing-trailer", None, "BinaryFormatter trailer byte is missing".to_string()).fixable());
+        repairs.push("Restored the BinaryFormatter trailer".to_string());
+    }
+
+    let payload = match SaveCrypto::remove_save_header(&framed) {
+        Ok(payload) => payload,
+        Err(e) => {
+            findings.push(Finding::new("error", "invalid-header", None, e.to_string()));
+            return None;
+        }
+    };
+
+    let mut base64_text: String = String::from_utf8_lossy(&payload).chars().filter(|c| !c.is_whitespace()).collect();
+    let unpadded_len = base64_text.trim_end_matches('=').len();
+    if base64_text.len() % 4 != 0 || base64_text.len() - unpadded_len > 2 {
+        base64_text.truncate(unpadded_len);
+        while base64_text.len() % 4 != 0 {
+            base64_text.push('=');
+        }
+        findings.push(Finding::new("error", "bad-base64-padding", None, "Base64 payload has wrong padding".to_string()).fixable());
+        repairs.push("Fixed base64 padding".to_string());
+    }
+
+    let encrypted = match general_purpose::STANDARD.decode(&base64_text) {
+        Ok(encrypted) => encrypted,
+        Err(e) => {
+            findings.push(Finding::new("error", "invalid-base64", None, format!("Payload is not valid base64: {}", e)));
+            return None;
+        }
+    };
+
+    let mut aligned = encrypted;
+    if aligned.len() % 16 != 0 {
+        aligned.truncate(aligned.len() - aligned.len() % 16);
+        findings.push(Finding::new("error", "truncated-ciphertext", None, "Encrypted data is not a whole number of blocks".to_string()).fixable());
+        repairs.push("Dropped a partial trailing cipher block".to_string());
+    }
+
+    let mut decrypted = match crypto.decrypt_raw(&aligned) {
+        Ok(decrypted) => decrypted,
+        Err(e) => {
+            findings.push(Finding::new("error", "decrypt-failed", None, e.to_string()));
+            return None;
+        }
+    };
+
+    let padding = decrypted.last().copied().unwrap_or(0) as usize;
+    let valid_padding = (1..=16).contains(&padding)
+        && padding <= decrypted.len()
+        && decrypted[decrypted.len() - padding..].iter().all(|&b| b as usize == padding);
+    if valid_padding {
+        decrypted.truncate(decrypted.len() - padding);
+    } else {
+        // leave the bytes in place, the json repair trims whatever follows the object
+        findings.push(Finding::new("warning", "bad-padding", None, "Decrypted data has invalid PKCS7 padding".to_string()).fixable());
+        repairs.push("Ignored invalid PKCS7 padding".to_string());
+    }
+
+    Some(decrypted)
+}
@@ -0,0 +1,372 @@
+use crate::host::Host;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+// offset between a 32-bit steam account id and its 64-bit steam id
+const STEAM_ID64_BASE: u64 = 76561197960265728;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VdfValue {
+    String(String),
+    Object(Vec<(String, VdfValue)>),
+}
+
+impl VdfValue {
+    pub fn get(&self, key: &str) -> Option<&VdfValue> {
+        match self {
+            VdfValue::Object(entries) => entries
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v),
+            VdfValue::String(_) => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            VdfValue::String(s) => Some(s),
+            VdfValue::Object(_) => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, VdfValue)] {
+        match self {
+            VdfValue::Object(entries) => entries,
+            VdfValue::String(_) => &[],
+        }
+    }
+}
+
+// minimal parser for valve's text keyvalues format (loginusers.vdf, remotecache.vdf)
+pub fn parse_vdf(content: &str) -> Result<VdfValue, Box<dyn std::error::Error>> {
+    let tokens = tokenize_vdf(content)?;
+    let mut pos = 0;
+    let root = parse_vdf_object(&tokens, &mut pos, true)?;
+    Ok(root)
+}
+
+#[derive(Debug, PartialEq)]
+enum VdfToken {
+    Text(String),
+    Open,
+    Close,
+}
+
+fn tokenize_vdf(content: &str) -> Result<Vec<VdfToken>, Box<dyn std::error::Error>> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(VdfToken::Open),
+            '}' => tokens.push(VdfToken::Close),
+            '"' => {
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('\\') => match chars.next() {
+                            Some('n') => text.push('\n'),
+                            Some('t') => text.push('\t'),
+                            Some(other) => text.push(other),
+                            None => return Err("Unterminated escape in VDF".into()),
+                        },
+                        Some('"') => break,
+                        Some(other) => text.push(other),
+                        None => return Err("Unterminated string in VDF".into()),
+                    }
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            c if c.is_whitespace() => {}
+            _ => {
+                // unquoted token, runs until whitespace or a brace
+                let mut text = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || next == '{' || next == '}' || next == '"' {
+                        break;
+                    }
+                    text.push(next);
+                    chars.next();
+                }
+                tokens.push(VdfToken::Text(text));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse_vdf_object(tokens: &[VdfToken], pos: &mut usize, root: bool) -> Result<VdfValue, Box<dyn std::error::Error>> {
+    let mut entries = Vec::new();
+
+    while *pos < tokens.len() {
+        let key = match &tokens[*pos] {
+            VdfToken::Close if !root => {
+                *pos += 1;
+                return Ok(VdfValue::Object(entries));
+            }
+            VdfToken::Text(key) => key.clone(),
+            _ => return Err("Unexpected token in VDF".into()),
+        };
+        *pos += 1;
+
+        let value = match tokens.get(*pos) {
+            Some(VdfToken::Text(value)) => {
+                *pos += 1;
+                VdfValue::String(value.clone())
+            }
+            Some(VdfToken::Open) => {
+                *pos += 1;
+                parse_vdf_object(tokens, pos, false)?
+            }
+            _ => return Err("Missing value in VDF".into()),
+        };
+        entries.push((key, value));
+    }
+
+    if root {
+        Ok(VdfValue::Object(entries))
+    } else {
+        Err("Unclosed object in VDF".into())
+    }
+}
+
+pub fn get_steam_dirs(host: &dyn Host) -> Vec<PathBuf> {
+    let mut dirs_found = Vec::new();
+
+    if let Some(program_files) = host.var("ProgramFiles(x86)") {
+        dirs_found.push(PathBuf::from(program_files).join("Steam"));
+    }
+    if let Some(program_files) = host.var("ProgramFiles") {
+        dirs_found.push(PathBuf::from(program_files).join("Steam"));
+    }
+    dirs_found.push(PathBuf::from("C:\\Program Files (x86)\\Steam"));
+
+    if let Some(home_dir) = host.home_dir() {
+        dirs_found.push(home_dir.join(".steam").join("steam"));
+        dirs_found.push(home_dir.join(".local").join("share").join("Steam"));
+        dirs_found.push(home_dir.join("Library").join("Application Support").join("Steam"));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    dirs_found
+        .into_iter()
+        .filter(|dir| host.is_dir(dir))
+        .filter(|dir| seen.insert(dir.to_string_lossy().to_lowercase()))
+        .collect()
+}
+
+// accepts either a 64-bit steam id or a 32-bit account id and returns the 64-bit form
+pub fn normalize_steam_id(id: &str) -> Option<u64> {
+    let value = id.parse::<u64>().ok()?;
+    if value < STEAM_ID64_BASE {
+        value.checked_add(STEAM_ID64_BASE)
+    } else {
+        Some(value)
+    }
+}
+
+pub fn parse_login_users(content: &str) -> Result<HashMap<u64, String>, Box<dyn std::error::Error>> {
+    let root = parse_vdf(content)?;
+    let mut users = HashMap::new();
+
+    let Some(list) = root.get("users") else {
+        return Ok(users);
+    };
+
+    for (id, user) in list.entries() {
+        let Ok(steam_id) = id.parse::<u64>() else {
+            continue;
+        };
+        let name = user
+            .get("PersonaName")
+            .or_else(|| user.get("AccountName"))
+            .and_then(|v| v.as_str());
+        if let Some(name) = name {
+            users.insert(steam_id, name.to_string());
+        }
+    }
+
+    Ok(users)
+}
+
+// steam id -> persona name for every account that has logged in on this machine
+pub fn load_login_users(host: &dyn Host) -> HashMap<u64, String> {
+    let mut users = HashMap::new();
+
+    for steam_dir in get_steam_dirs(host) {
+        let Ok(content) = host.read_to_string(&steam_dir.join("config").join("loginusers.vdf")) else {
+            continue;
+        };
+        if let Ok(parsed) = parse_login_users(&content) {
+            for (id, name) in parsed {
+                users.entry(id).or_insert(name);
+            }
+        }
+    }
+
+    users
+}
+
+pub fn resolve_persona_name(users: &HashMap<u64, String>, steam_id: &str) -> Option<String> {
+    normalize_steam_id(steam_id).and_then(|id| users.get(&id).cloned())
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct CloudFileState {
+    pub file: String,
+    pub account_id: u32,
+    #[ts(type = "number | null")]
+    pub sync_state: Option<i64>,
+    pub status: String,
+    pub sha: Option<String>,
+    #[ts(type = "number | null")]
+    pub size: Option<u64>,
+    #[ts(type = "number | null")]
+    pub local_time: Option<i64>,
+    #[ts(type = "number | null")]
+    pub remote_time: Option<i64>,
+    pub local_sha: Option<String>,
+    pub matches_local: Option<bool>,
+}
+
+impl CloudFileState {
+    // true when steam's record says something other than "this exact file is in the cloud"
+    pub fn differs_from_local(&self) -> bool {
+        self.matches_local == Some(false) || self.sync_state.map_or(false, |state| state != 1)
+    }
+}
+
+fn describe_sync_state(state: Option<i64>) -> &'static str {
+    match state {
+        Some(1) => "synced",
+        Some(2) => "local-changes",
+        Some(3) => "remote-changes",
+        Some(4) => "conflict",
+        Some(_) => "unknown",
+        None => "untracked",
+    }
+}
+
+pub fn parse_remote_cache(content: &str, account_id: u32) -> Result<Vec<CloudFileState>, Box<dyn std::error::Error>> {
+    let root = parse_vdf(content)?;
+    let mut files = Vec::new();
+
+    // the root holds a single object named after the app id
+    for (_, app) in root.entries() {
+        for (file, record) in app.entries() {
+            if !matches!(record, VdfValue::Object(_)) {
+                continue;
+            }
+            let number = |key: &str| record.get(key).and_then(|v| v.as_str()).and_then(|v| v.parse::<i64>().ok());
+            let sync_state = number("syncstate");
+            files.push(CloudFileState {
+                file: file.replace('\\', "/"),
+                account_id,
+                sync_state,
+                status: describe_sync_state(sync_state).to_string(),
+                sha: record.get("sha").and_then(|v| v.as_str()).map(|s| s.to_lowercase()),
+                size: number("size").and_then(|v| u64::try_from(v).ok()),
+                local_time: number("localtime"),
+                remote_time: number("remotetime"),
+                local_sha: None,
+                matches_local: None,
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+// every remotecache.vdf for the app, optionally narrowed to one account
+pub fn load_remote_caches(host: &dyn Host, app_id: u32, account_id: Option<u32>) -> Vec<CloudFileState> {
+    let mut states = Vec::new();
+
+    for steam_dir in get_steam_dirs(host) {
+        let Ok(entries) = host.read_dir(&steam_dir.join("userdata")) else {
+            continue;
+        };
+        for entry in entries {
+            let Some(Ok(id)) = entry.file_name().and_then(|name| name.to_str()).map(|name| name.parse::<u32>()) else {
+                continue;
+            };
+            if account_id.map_or(false, |wanted| wanted != id) {
+                continue;
+            }
+            let cache_file = entry.join(app_id.to_string()).join("remotecache.vdf");
+            let Ok(content) = host.read_to_string(&cache_file) else {
+                continue;
+            };
+            if let Ok(parsed) = parse_remote_cache(&content, id) {
+                states.extend(parsed);
+            }
+        }
+    }
+
+    states
+}
+
+pub fn account_id_from_steam_id(steam_id: &str) -> Option<u32> {
+    normalize_steam_id(steam_id)
+        .and_then(|id| id.checked_sub(STEAM_ID64_BASE))
+        .and_then(|id| u32::try_from(id).ok())
+}
+
+// numeric parent folder of a silksong save, if any
+pub fn steam_id_for_save(save_path: &Path) -> Option<String> {
+    let parent = save_path.parent()?.file_name()?.to_str()?;
+    if !parent.is_empty() && parent.chars().all(|c| c.is_ascii_digit()) {
+        Some(parent.to_string())
+    } else {
+        None
+    }
+}
+
+pub fn sha1_hex(data: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(data);
+    format!("{:x}", hasher.finalize())
+}
+
+pub fn get_cloud_state(host: &dyn Host, app_id: u32, save_path: &Path) -> Option<CloudFileState> {
+    let file_name = save_path.file_name()?.to_str()?;
+    let steam_id = steam_id_for_save(save_path);
+    let account_id = steam_id.as_deref().and_then(account_id_from_steam_id);
+
+    let mut matches = load_remote_caches(host, app_id, account_id).into_iter().filter(|state| {
+        let mut parts = state.file.rsplit('/');
+        if parts.next() != Some(file_name) {
+            return false;
+        }
+        match (parts.next(), steam_id.as_deref()) {
+            (Some(folder), Some(id)) => folder == id,
+            _ => true,
+        }
+    });
+    let mut state = matches.next()?;
+    // hollow knight saves carry no steam id, so on a shared pc the record could be another
+    // user's; it only counts when a single account has the file
+    if steam_id.is_none() && matches.any(|other| other.account_id != state.account_id) {
+        return None;
+    }
+
+    if let Ok(data) = host.read(save_path) {
+        let local_sha = sha1_hex(&data);
+        state.matches_local = state.sha.as_ref().map(|sha| *sha == local_sha);
+        state.local_sha = Some(local_sha);
+    }
+
+    Some(state)
+}
@@ -0,0 +1,245 @@
+use crate::host::Host;
+use crate::{ftp, webdav};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+// bump when a field changes meaning and add a step to `migrate`
+pub const CONFIG_VERSION: u32 = 1;
+
+// the profile every game starts with; it keeps the original `<game>-sync.json` file name
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct SyncConfig {
+    pub version: u32,
+    pub pc_save: String,
+    pub switch_save: String,
+    pub last_sync: Option<DateTime<Utc>>,
+    // which userN.dat the save is stored as on the switch; None keeps the pc file name
+    pub switch_slot: Option<u8>,
+    pub git_history: bool,
+    pub webdav_username: Option<String>,
+    pub webdav_password: Option<String>,
+    pub ftp_username: Option<String>,
+    pub ftp_password: Option<String>,
+    // set by load when a broken file was moved aside, never written to disk
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub quarantined: Option<String>,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            pc_save: String::new(),
+            switch_save: String::new(),
+            last_sync: None,
+            switch_slot: None,
+            git_history: true,
+            webdav_username: None,
+            webdav_password: None,
+            ftp_username: None,
+            ftp_password: None,
+            quarantined: None,
+        }
+    }
+}
+
+impl SyncConfig {
+    pub fn is_configured(&self) -> bool {
+        !self.pc_save.is_empty() && !self.switch_save.is_empty()
+    }
+
+    pub fn switch_file_name(&self) -> Option<String> {
+        self.switch_slot.map(|slot| format!("user{}.dat", slot))
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.version != CONFIG_VERSION {
+            return Err(format!("Unsupported config version {}", self.version));
+        }
+        if webdav::is_webdav_url(&self.pc_save) || ftp::is_ftp_url(&self.pc_save) {
+            return Err("The PC save must be a local file, only the Switch side can be remote".to_string());
+        }
+        if !self.pc_save.is_empty() && self.pc_save == self.switch_save {
+            return Err("PC and Switch save paths must be different".to_string());
+        }
+        if ftp::is_ftp_url(&self.switch_save) {
+            ftp::FtpTarget::from_url(&self.switch_save, "/", None, None)
+                .map_err(|e| format!("Invalid FTP address: {}", e))?;
+        }
+        if let Some(slot) = self.switch_slot {
+            if !(1..=4).contains(&slot) {
+                return Err(format!("Switch slot must be between 1 and 4, got {}", slot));
+            }
+        }
+        if let Some(last_sync) = self.last_sync {
+            if last_sync > Utc::now() + chrono::Duration::days(1) {
+                return Err("lastSync is in the future".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn non_empty(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(|s| s.to_string())
+}
+
+// version 0 is the untyped `{pcSave, switchSave, lastSync}` file the frontend used to write
+// directly, optionally with credentials and gitHistory mixed in
+fn migrate_v0(raw: &Value) -> SyncConfig {
+    SyncConfig {
+        version: 1,
+        pc_save: raw["pcSave"].as_str().unwrap_or("").to_string(),
+        switch_save: raw["switchSave"].as_str().unwrap_or("").to_string(),
+        last_sync: raw["lastSync"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc)),
+        switch_slot: None,
+        git_history: raw["gitHistory"].as_bool().unwrap_or(true),
+        webdav_username: non_empty(&raw["webdavUsername"]),
+        webdav_password: non_empty(&raw["webdavPassword"]),
+        ftp_username: non_empty(&raw["ftpUsername"]),
+        ftp_password: non_empty(&raw["ftpPassword"]),
+        quarantined: None,
+    }
+}
+
+// only the shape and version are checked here; the rules in `validate` apply when saving, so a
+// readable file that breaks one (a clock-skewed lastSync, say) is loaded rather than thrown away
+pub fn migrate(raw: Value) -> Result<SyncConfig, String> {
+    if !raw.is_object() {
+        return Err("Config is not a JSON object".to_string());
+    }
+
+    match version_of(&raw)? {
+        0 => Ok(migrate_v0(&raw)),
+        1 => serde_json::from_value(raw).map_err(|e| e.to_string()),
+        newer => Err(newer_version_error(newer)),
+    }
+}
+
+// files without a version predate it
+fn version_of(raw: &Value) -> Result<u64, String> {
+    raw.get("version").map_or(Some(0), |v| v.as_u64()).ok_or_else(|| "Config version is not a number".to_string())
+}
+
+fn newer_version(raw: &Value) -> Option<u64> {
+    version_of(raw).ok().filter(|&version| version > u64::from(CONFIG_VERSION))
+}
+
+fn newer_version_error(version: u64) -> String {
+    format!("Config version {} was written by a newer Hollow Sync, update to use this profile", version)
+}
+
+// a file that isn't utf-8 is as unreadable as one that isn't json
+fn parse(content: Vec<u8>) -> Result<Value, String> {
+    let content = String::from_utf8(content).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+// moves a config that can't be read out of the way so the next save starts clean
+// instead of failing the same way forever
+fn quarantine(host: &dyn Host, config_file: &Path) -> Option<String> {
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S");
+    let target = config_file.with_extension(format!("json.bad-{}", stamp));
+    host.rename(config_file, &target).ok()?;
+    Some(target.to_string_lossy().to_string())
+}
+
+// a file written by a newer build is an error and stays where it is, so running an older
+// build once doesn't throw it away; only files that can't be parsed are quarantined
+pub fn load(host: &dyn Host, config_file: &Path) -> Result<SyncConfig, String> {
+    let content = match host.read(config_file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(SyncConfig::default()),
+        Err(e) => return Err(format!("Could not read {}: {}", config_file.display(), e)),
+    };
+
+    let raw = parse(content);
+    if let Some(newer) = raw.as_ref().ok().and_then(newer_version) {
+        return Err(newer_version_error(newer));
+    }
+
+    match raw.and_then(migrate) {
+        Ok(config) => Ok(config),
+        Err(_) => Ok(SyncConfig {
+            quarantined: quarantine(host, config_file),
+            ..SyncConfig::default()
+        }),
+    }
+}
+
+pub fn save(host: &dyn Host, config_file: &Path, config: &SyncConfig) -> Result<(), Box<dyn std::error::Error>> {
+    config.validate()?;
+    // whatever the caller still holds, a newer build's file is never written over
+    if let Some(newer) = host.read(config_file).ok().and_then(|content| parse(content).ok()).as_ref().and_then(newer_version) {
+        return Err(newer_version_error(newer).into());
+    }
+
+    let config = SyncConfig {
+        quarantined: None,
+        ..config.clone()
+    };
+    // write then rename so a crash mid-write can't leave a half file behind
+    let temp_file = config_file.with_extension("json.tmp");
+    host.write(&temp_file, serde_json::to_string_pretty(&config)?.as_bytes())?;
+    host.rename(&temp_file, config_file)?;
+    Ok(())
+}
+
+// names end up in file names, so keep them to characters every filesystem accepts
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.trim().is_empty()
+        && name.len() <= 40
+        && name.trim() == name
+        && name.chars().all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid profile name \"{}\": use up to 40 letters, digits, spaces, - or _", name))
+    }
+}
+
+pub fn profile_file(config_dir: &Path, game: &str, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        config_dir.join(format!("{}-sync.json", game))
+    } else {
+        config_dir.join(format!("{}-sync.{}.json", game, profile))
+    }
+}
+
+// the default profile is always listed, even before its file has been written
+pub fn list_profiles(host: &dyn Host, config_dir: &Path, game: &str) -> Vec<String> {
+    let prefix = format!("{}-sync.", game);
+    let mut profiles: Vec<String> = host.read_dir(config_dir)
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| entry.file_name().and_then(|name| name.to_str()).map(|name| name.to_string()))
+                .filter_map(|name| Some(name.strip_prefix(&prefix)?.strip_suffix(".json")?.to_string()))
+                .filter(|profile| profile != DEFAULT_PROFILE && validate_profile_name(profile).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+// key for per-profile state such as watchers; plain game keys stay valid for the default profile
+pub fn profile_key(game: &str, profile: &str) -> String {
+    if profile == DEFAULT_PROFILE {
+        game.to_string()
+    } else {
+        format!("{}/{}", game, profile)
+    }
+}
@@ -0,0 +1,172 @@
+use crate::host::Host;
+use crate::save_data::SaveSummary;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use ts_rs::TS;
+
+// one line of sync-log.jsonl; hashes are sha1 of the decoded save json, so the same
+// save has the same hash whether it is stored as a pc .dat or as switch json
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SyncLogEntry {
+    pub timestamp: DateTime<Utc>,
+    pub operation: String,
+    pub game: Option<String>,
+    pub profile: Option<String>,
+    pub direction: Option<String>,
+    pub source: Option<String>,
+    pub destination: Option<String>,
+    pub source_sha: Option<String>,
+    pub destination_sha_before: Option<String>,
+    pub destination_sha_after: Option<String>,
+    pub source_summary: Option<SaveSummary>,
+    pub destination_summary_before: Option<SaveSummary>,
+    pub snapshot: Option<String>,
+    // raw pre-sync copy of the destination, kept so the newest sync can be undone
+    pub undo_copy: Option<String>,
+    // the sync wrote a file that wasn't there before, so undoing it removes the file
+    #[serde(default)]
+    pub destination_created: bool,
+    // on undo entries, the timestamp of the sync they reverted
+    pub reverts: Option<DateTime<Utc>>,
+    // filled in by read from later undo entries, never written to disk
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub reverted: Option<DateTime<Utc>>,
+    pub outcome: String,
+    pub error: Option<String>,
+    // problems that didn't fail the operation, like a history snapshot that couldn't be saved
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl SyncLogEntry {
+    pub fn new(operation: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            operation: operation.to_string(),
+            game: None,
+            profile: None,
+            direction: None,
+            source: None,
+            destination: None,
+            source_sha: None,
+            destination_sha_before: None,
+            destination_sha_after: None,
+            source_summary: None,
+            destination_summary_before: None,
+            snapshot: None,
+            undo_copy: None,
+            destination_created: false,
+            reverts: None,
+            reverted: None,
+            outcome: "pending".to_string(),
+            error: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn for_profile(mut self, game: &str, profile: &str) -> Self {
+        self.game = Some(game.to_string());
+        self.profile = Some(profile.to_string());
+        self
+    }
+
+    pub fn set_paths(&mut self, direction: Option<&str>, source: &str, destination: &str) {
+        self.direction = direction.map(|d| d.to_string());
+        self.source = Some(source.to_string());
+        self.destination = Some(destination.to_string());
+    }
+
+    pub fn finish<T>(&mut self, result: &Result<T, Box<dyn std::error::Error>>) {
+        match result {
+            Ok(_) => self.outcome = "success".to_string(),
+            Err(e) => {
+                self.outcome = "error".to_string();
+                self.error = Some(e.to_string());
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, TS)]
+#[serde(rename_all = "camelCase", default)]
+#[ts(export)]
+pub struct SyncLogFilter {
+    #[ts(optional)]
+    pub game: Option<String>,
+    #[ts(optional)]
+    pub profile: Option<String>,
+    #[ts(optional)]
+    pub operation: Option<String>,
+    #[ts(optional)]
+    pub outcome: Option<String>,
+    #[ts(optional)]
+    pub since: Option<DateTime<Utc>>,
+    #[ts(optional)]
+    pub until: Option<DateTime<Utc>>,
+    // matched against the source and destination paths
+    #[ts(optional)]
+    pub path: Option<String>,
+    #[ts(optional)]
+    pub limit: Option<usize>,
+}
+
+impl SyncLogFilter {
+    pub fn matches(&self, entry: &SyncLogEntry) -> bool {
+        let same = |wanted: &Option<String>, actual: &Option<String>| wanted.is_none() || wanted == actual;
+        let path_matches = self.path.as_ref().map_or(true, |path| {
+            [&entry.source, &entry.destination].iter().any(|p| p.as_ref().map_or(false, |p| p.contains(path.as_str())))
+        });
+
+        same(&self.game, &entry.game)
+            && same(&self.profile, &entry.profile)
+            && self.operation.as_ref().map_or(true, |op| *op == entry.operation)
+            && self.outcome.as_ref().map_or(true, |outcome| *outcome == entry.outcome)
+            && self.since.map_or(true, |since| entry.timestamp >= since)
+            && self.until.map_or(true, |until| entry.timestamp <= until)
+            && path_matches
+    }
+}
+
+pub fn append(host: &dyn Host, log_file: &Path, entry: &SyncLogEntry) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = log_file.parent() {
+        host.create_dir_all(parent)?;
+    }
+    // a torn last line would swallow this entry, so start on a fresh line after one
+    let mut line = serde_json::to_string(entry)? + "\n";
+    if matches!(host.last_byte(log_file), Ok(Some(last)) if last != b'\n') {
+        line.insert(0, '\n');
+    }
+    host.append(log_file, line.as_bytes())?;
+    Ok(())
+}
+
+// newest first; lines that don't parse (e.g. a torn final write) are skipped, and
+// entries undone by a later successful undo come back with `reverted` set
+pub fn read(host: &dyn Host, log_file: &Path, filter: &SyncLogFilter) -> Result<Vec<SyncLogEntry>, Box<dyn std::error::Error>> {
+    let content = match host.read_to_string(log_file) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries: Vec<SyncLogEntry> = content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect();
+    let undos: Vec<(DateTime<Utc>, DateTime<Utc>)> = entries
+        .iter()
+        .filter(|entry| entry.operation == "undo" && entry.outcome == "success")
+        .filter_map(|entry| entry.reverts.map(|reverts| (reverts, entry.timestamp)))
+        .collect();
+    for entry in &mut entries {
+        entry.reverted = undos.iter().find(|(reverts, _)| *reverts == entry.timestamp).map(|(_, at)| *at);
+    }
+
+    entries.retain(|entry| filter.matches(entry));
+    entries.reverse();
+    if let Some(limit) = filter.limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
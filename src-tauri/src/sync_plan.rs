@@ -0,0 +1,84 @@
+use crate::save_manager::FileInfo;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+
+// everything sync would do, worked out without writing anything; sync itself runs from
+// this plan so the dry run and the real run can't disagree
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct SyncPlan {
+    pub game: String,
+    pub profile: String,
+    pub direction: String,
+    pub reason: String,
+    pub source: String,
+    pub destination: String,
+    pub reads: Vec<String>,
+    pub writes: Vec<String>,
+    pub layout: String,
+    pub history_snapshot: bool,
+    pub undoable: bool,
+    pub conflicts: Vec<String>,
+    pub warnings: Vec<String>,
+    // reasons sync would refuse to run; forcing turns them into warnings
+    pub blockers: Vec<String>,
+}
+
+impl SyncPlan {
+    pub fn is_blocked(&self) -> bool {
+        !self.blockers.is_empty()
+    }
+}
+
+// returns the direction and why it was picked
+pub fn choose_direction(pc_info: &FileInfo, switch_info: &FileInfo, force_direction: Option<String>) -> Result<(String, String), String> {
+    let pc_exists = pc_info.exists;
+    let switch_exists = switch_info.exists;
+
+    if !pc_exists && !switch_exists {
+        return Err("No save files found at configured paths".to_string());
+    }
+
+    if let Some(direction) = force_direction {
+        if direction != "pc-to-switch" && direction != "switch-to-pc" {
+            return Err("Invalid sync direction".to_string());
+        }
+        let (source_exists, side) = if direction == "pc-to-switch" { (pc_exists, "PC") } else { (switch_exists, "Switch") };
+        if !source_exists {
+            return Err(format!("There is no {} save to sync from", side));
+        }
+        return Ok((direction, "chosen explicitly".to_string()));
+    }
+
+    Ok(if !pc_exists {
+        ("switch-to-pc".to_string(), "there is no PC save yet".to_string())
+    } else if !switch_exists {
+        ("pc-to-switch".to_string(), "there is no Switch save yet".to_string())
+    } else if pc_info.modified > switch_info.modified {
+        ("pc-to-switch".to_string(), "the PC save is newer".to_string())
+    } else {
+        ("switch-to-pc".to_string(), "the Switch save is newer (or both are the same age)".to_string())
+    })
+}
+
+// both sides moving on since the last sync means one of them loses progress
+pub fn find_conflicts(last_sync: Option<DateTime<Utc>>, pc_info: &FileInfo, switch_info: &FileInfo, direction: &str) -> Vec<String> {
+    let last_sync = match last_sync {
+        Some(last_sync) => last_sync,
+        None => return Vec::new(),
+    };
+    let pc_changed = pc_info.modified.map_or(false, |m| m > last_sync);
+    let switch_changed = switch_info.modified.map_or(false, |m| m > last_sync);
+    if !(pc_changed && switch_changed) {
+        return Vec::new();
+    }
+
+    let lost = if direction == "pc-to-switch" { "Switch" } else { "PC" };
+    vec![format!(
+        "Both saves changed since the last sync ({}), progress made only on the {} side will be overwritten",
+        last_sync.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+        lost
+    )]
+}
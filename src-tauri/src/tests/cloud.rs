@@ -0,0 +1,31 @@
+use super::{pc_save, setup};
+use crate::steam;
+
+const PC: &str = "/home/player/AppData/LocalLow/Team Cherry/Hollow Knight/user1.dat";
+const USERDATA: &str = "/home/player/.local/share/Steam/userdata";
+
+fn remote_cache(sha: &str) -> String {
+    format!("\"367520\"\n{{\n\t\"user1.dat\"\n\t{{\n\t\t\"syncstate\"\t\t\"1\"\n\t\t\"sha\"\t\t\"{}\"\n\t}}\n}}\n", sha)
+}
+
+#[tokio::test]
+async fn a_single_account_with_the_file_is_trusted() {
+    let (host, manager) = setup();
+    host.add_file(PC, pc_save(100));
+    host.add_file(format!("{}/111/367520/remotecache.vdf", USERDATA), remote_cache("abc"));
+
+    let state = manager.get_cloud_state("hk", PC).await.unwrap().expect("cloud record");
+    assert_eq!(state.account_id, 111);
+    assert_eq!(state.local_sha, Some(steam::sha1_hex(&pc_save(100))));
+    assert_eq!(state.matches_local, Some(false));
+}
+
+#[tokio::test]
+async fn another_users_record_is_not_guessed_at() {
+    let (host, manager) = setup();
+    host.add_file(PC, pc_save(100));
+    host.add_file(format!("{}/111/367520/remotecache.vdf", USERDATA), remote_cache("abc"));
+    host.add_file(format!("{}/222/367520/remotecache.vdf", USERDATA), remote_cache("def"));
+
+    assert!(manager.get_cloud_state("hk", PC).await.unwrap().is_none());
+}
@@ -0,0 +1,132 @@
+use super::setup;
+use crate::host::Host;
+use crate::sync_config::{SyncConfig, DEFAULT_PROFILE};
+use std::path::Path;
+
+const CONFIG_DIR: &str = "/home/player/.local/share/HollowSync/config";
+
+fn config() -> SyncConfig {
+    SyncConfig {
+        pc_save: "/home/player/user1.dat".to_string(),
+        switch_save: "http://192.168.1.20:8080/JKSV/Hollow Knight".to_string(),
+        switch_slot: Some(3),
+        git_history: false,
+        webdav_username: Some("knight".to_string()),
+        ..SyncConfig::default()
+    }
+}
+
+#[tokio::test]
+async fn configs_round_trip_through_the_default_profile_file() {
+    let (host, manager) = setup();
+    assert_eq!(manager.load_config("hk", DEFAULT_PROFILE).await.unwrap(), SyncConfig::default());
+
+    manager.save_config("hk", DEFAULT_PROFILE, &config()).await.unwrap();
+    assert_eq!(manager.load_config("hk", DEFAULT_PROFILE).await.unwrap(), config());
+    assert!(host.is_file(&Path::new(CONFIG_DIR).join("hk-sync.json")));
+    assert!(!host.exists(&Path::new(CONFIG_DIR).join("hk-sync.json.tmp")));
+    assert_eq!(manager.load_config("silksong", DEFAULT_PROFILE).await.unwrap(), SyncConfig::default());
+}
+
+#[tokio::test]
+async fn invalid_configs_are_refused() {
+    let (_, manager) = setup();
+    let same_paths = SyncConfig { switch_save: "/home/player/user1.dat".to_string(), ..config() };
+
+    let error = manager.save_config("hk", DEFAULT_PROFILE, &same_paths).await.unwrap_err();
+    assert_eq!(error.to_string(), "PC and Switch save paths must be different");
+    assert!(manager.save_config("hk", "../escape", &config()).await.is_err());
+}
+
+#[tokio::test]
+async fn version_zero_files_are_migrated() {
+    let (host, manager) = setup();
+    host.add_file(
+        Path::new(CONFIG_DIR).join("hk-sync.json"),
+        r#"{"pcSave":"/home/player/user1.dat","switchSave":"/sdmc/user1.json","lastSync":"2024-01-01T12:00:00Z","gitHistory":false,"ftpUsername":""}"#,
+    );
+
+    let loaded = manager.load_config("hk", DEFAULT_PROFILE).await.unwrap();
+    assert_eq!(loaded.version, 1);
+    assert_eq!(loaded.switch_save, "/sdmc/user1.json");
+    assert_eq!(loaded.last_sync.map(|time| time.to_rfc3339()), Some("2024-01-01T12:00:00+00:00".to_string()));
+    assert!(!loaded.git_history);
+    assert_eq!(loaded.ftp_username, None);
+}
+
+#[tokio::test]
+async fn broken_configs_are_quarantined() {
+    let (host, manager) = setup();
+    let file = Path::new(CONFIG_DIR).join("hk-sync.json");
+    host.add_file(&file, "{ not json");
+
+    let loaded = manager.load_config("hk", DEFAULT_PROFILE).await.unwrap();
+    let quarantined = loaded.quarantined.clone().expect("config was quarantined");
+    assert_eq!(loaded, SyncConfig { quarantined: Some(quarantined.clone()), ..SyncConfig::default() });
+    assert!(!host.exists(&file));
+    assert_eq!(host.read_to_string(Path::new(&quarantined)).unwrap(), "{ not json");
+
+    // the next save starts clean and never writes the marker
+    manager.save_config("hk", DEFAULT_PROFILE, &loaded).await.unwrap();
+    assert_eq!(manager.load_config("hk", DEFAULT_PROFILE).await.unwrap(), SyncConfig::default());
+}
+
+#[tokio::test]
+async fn configs_from_a_newer_build_are_left_alone() {
+    let (host, manager) = setup();
+    let file = Path::new(CONFIG_DIR).join("hk-sync.json");
+    let newer = r#"{"version":2,"pcSave":"/home/player/user1.dat","switchSave":"/sdmc/user1.json"}"#;
+    host.add_file(&file, newer);
+
+    let error = manager.load_config("hk", DEFAULT_PROFILE).await.unwrap_err();
+    assert_eq!(error.to_string(), "Config version 2 was written by a newer Hollow Sync, update to use this profile");
+    assert!(manager.save_config("hk", DEFAULT_PROFILE, &config()).await.is_err());
+    assert_eq!(host.read_to_string(&file).unwrap(), newer);
+    assert_eq!(host.read_dir(Path::new(CONFIG_DIR)).unwrap(), vec![file]);
+}
+
+#[tokio::test]
+async fn configs_that_are_not_utf8_are_quarantined() {
+    let (host, manager) = setup();
+    let file = Path::new(CONFIG_DIR).join("hk-sync.json");
+    host.add_file(&file, vec![0xff, 0xfe, b'{']);
+
+    let loaded = manager.load_config("hk", DEFAULT_PROFILE).await.unwrap();
+    let quarantined = loaded.quarantined.expect("config was quarantined");
+    assert!(!host.exists(&file));
+    assert_eq!(host.read(Path::new(&quarantined)).unwrap(), vec![0xff, 0xfe, b'{']);
+}
+
+#[tokio::test]
+async fn profiles_are_created_renamed_and_deleted() {
+    let (host, manager) = setup();
+    manager.save_config("hk", DEFAULT_PROFILE, &config()).await.unwrap();
+
+    manager.clone_profile("hk", DEFAULT_PROFILE, "Steam Deck").await.unwrap();
+    manager.create_profile("hk", "Laptop").await.unwrap();
+    assert_eq!(manager.list_profiles("hk").await.unwrap(), vec!["default", "Laptop", "Steam Deck"]);
+    assert_eq!(manager.load_config("hk", "Steam Deck").await.unwrap(), config());
+    assert!(manager.create_profile("hk", "Laptop").await.is_err());
+
+    manager.rename_profile("hk", "Steam Deck", "Deck").await.unwrap();
+    manager.delete_profile("hk", "Laptop").await.unwrap();
+    assert_eq!(manager.list_profiles("hk").await.unwrap(), vec!["default", "Deck"]);
+    assert!(host.is_file(&Path::new(CONFIG_DIR).join("hk-sync.Deck.json")));
+    assert!(manager.delete_profile("hk", "Laptop").await.is_err());
+}
+
+#[tokio::test]
+async fn readable_configs_that_break_a_rule_are_still_loaded() {
+    let (host, manager) = setup();
+    let file = Path::new(CONFIG_DIR).join("hk-sync.json");
+    host.add_file(
+        &file,
+        r#"{"version":1,"pcSave":"/home/player/user1.dat","switchSave":"/home/player/user1.dat","lastSync":"2999-01-01T00:00:00Z"}"#,
+    );
+
+    let loaded = manager.load_config("hk", DEFAULT_PROFILE).await.unwrap();
+    assert_eq!(loaded.quarantined, None);
+    assert_eq!(loaded.switch_save, "/home/player/user1.dat");
+    assert!(host.exists(&file));
+    assert!(manager.save_config("hk", DEFAULT_PROFILE, &loaded).await.is_err());
+}
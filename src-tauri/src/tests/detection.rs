@@ -0,0 +1,77 @@
+use super::{pc_save, setup, setup_with, HOME};
+use crate::host::MemoryHost;
+
+const HK_DIR: &str = "/home/player/AppData/LocalLow/Team Cherry/Hollow Knight";
+const SILKSONG_DIR: &str = "/home/player/AppData/LocalLow/Team Cherry/Hollow Knight Silksong";
+
+#[tokio::test]
+async fn finds_slots_with_their_restore_points() {
+    let (host, manager) = setup();
+    host.add_file(format!("{}/user1.dat", HK_DIR), pc_save(100));
+    host.add_file(format!("{}/user1_1.4.3.2.dat", HK_DIR), pc_save(50));
+    host.add_file(format!("{}/user1.dat.bak1", HK_DIR), pc_save(75));
+    host.add_file(format!("{}/user3.dat", HK_DIR), pc_save(300));
+    host.add_file(format!("{}/user5.dat", HK_DIR), pc_save(500));
+    host.add_file(format!("{}/shared.dat", HK_DIR), "not a slot");
+
+    let saves = manager.detect_all_saves(Some("hk".to_string())).await.unwrap();
+
+    let slots: Vec<u8> = saves.iter().map(|save| save.slot).collect();
+    assert_eq!(slots, vec![3, 1], "newest slot first, user5 is not a slot");
+    let slot1 = &saves[1];
+    assert_eq!(slot1.game, "hk");
+    assert_eq!(slot1.steam_id, None);
+    assert_eq!(slot1.base_path.to_str(), Some(HK_DIR));
+
+    let kinds: Vec<(&str, Option<&str>)> = slot1
+        .restore_points
+        .iter()
+        .map(|point| (point.kind.as_str(), point.version.as_deref()))
+        .collect();
+    assert_eq!(kinds, vec![("bak", Some("1.5.78.11833")), ("versioned", Some("1.4.3.2"))]);
+    assert!(saves[0].restore_points.is_empty());
+}
+
+#[tokio::test]
+async fn finds_silksong_saves_in_steam_id_folders() {
+    let (host, manager) = setup();
+    host.add_file(format!("{}/76561198000000001/user2.dat", SILKSONG_DIR), pc_save(20));
+    host.add_file(format!("{}/76561198000000002/user1.dat", SILKSONG_DIR), pc_save(10));
+    host.add_file(format!("{}/backups/user4.dat", SILKSONG_DIR), pc_save(40));
+
+    let saves = manager.detect_all_saves(Some("silksong".to_string())).await.unwrap();
+
+    let found: Vec<(u8, Option<&str>)> = saves.iter().map(|save| (save.slot, save.steam_id.as_deref())).collect();
+    assert_eq!(found, vec![(1, Some("76561198000000002")), (2, Some("76561198000000001"))]);
+    assert!(saves.iter().all(|save| save.game == "silksong" && save.steam_name.is_none()));
+}
+
+#[tokio::test]
+async fn searches_both_games_without_a_filter() {
+    let (host, manager) = setup();
+    host.add_file(format!("{}/user1.dat", HK_DIR), pc_save(1));
+    host.add_file(format!("{}/76561198000000001/user1.dat", SILKSONG_DIR), pc_save(2));
+
+    let mut games: Vec<String> = manager.detect_all_saves(None).await.unwrap().into_iter().map(|save| save.game).collect();
+    games.sort();
+    assert_eq!(games, vec!["hk", "silksong"]);
+}
+
+#[tokio::test]
+async fn uses_the_windows_profile_from_username() {
+    let windows_dir = "C:/Users/Hornet/AppData/LocalLow/Team Cherry/Hollow Knight";
+    let (host, manager) = setup_with(MemoryHost::new().with_var("USERNAME", "Hornet"));
+    host.add_file(format!("{}/user2.dat", windows_dir), pc_save(2));
+
+    let saves = manager.detect_all_saves(Some("hk".to_string())).await.unwrap();
+    assert_eq!(saves.len(), 1);
+    assert_eq!(saves[0].directory.to_str(), Some(windows_dir));
+}
+
+#[tokio::test]
+async fn an_empty_home_has_no_saves() {
+    let (host, manager) = setup();
+    host.add_file(format!("{}/Documents/user1.dat", HOME), pc_save(1));
+
+    assert!(manager.detect_all_saves(None).await.unwrap().is_empty());
+}
@@ -0,0 +1,118 @@
+use super::{configure, geo, pc_save, setup, switch_save};
+use crate::host::Host;
+use crate::sync_config::DEFAULT_PROFILE;
+use crate::sync_log::SyncLogFilter;
+use std::time::Duration;
+
+const PC: &str = "/home/player/AppData/LocalLow/Team Cherry/Hollow Knight/user1.dat";
+const SWITCH: &str = "/sdmc/saves/user1.json";
+
+#[tokio::test]
+async fn the_newer_side_wins() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(PC, pc_save(100));
+    host.add_file(SWITCH, switch_save(200));
+
+    let plan = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(plan.direction, "switch-to-pc");
+    assert_eq!(plan.reads, vec![SWITCH.to_string()]);
+    assert_eq!(plan.writes, vec![PC.to_string()]);
+
+    host.set_modified(PC, host.now() + Duration::from_secs(60));
+    let plan = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(plan.direction, "pc-to-switch");
+    assert_eq!(plan.reason, "the PC save is newer");
+    assert_eq!(plan.layout, "plain Switch JSON file");
+}
+
+#[tokio::test]
+async fn a_missing_side_is_synced_to() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(SWITCH, switch_save(200));
+
+    let plan = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!((plan.direction.as_str(), plan.reason.as_str()), ("switch-to-pc", "there is no PC save yet"));
+
+    let result = manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(result.direction, "switch-to-pc");
+    assert_eq!(geo(&manager, PC).await, 200);
+}
+
+#[tokio::test]
+async fn forcing_needs_a_save_to_sync_from() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+
+    let error = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap_err();
+    assert_eq!(error.to_string(), "No save files found at configured paths");
+
+    host.add_file(SWITCH, switch_save(200));
+    let error = manager.plan_sync("hk", DEFAULT_PROFILE, Some("pc-to-switch".to_string()), false).await.unwrap_err();
+    assert_eq!(error.to_string(), "There is no PC save to sync from");
+}
+
+#[tokio::test]
+async fn sync_converts_writes_and_logs() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(SWITCH, switch_save(200));
+    host.add_file(PC, pc_save(100));
+
+    let result = manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(result.direction, "pc-to-switch");
+    assert!(result.undoable);
+    assert_eq!(geo(&manager, SWITCH).await, 100);
+    assert!(manager.load_config("hk", DEFAULT_PROFILE).await.unwrap().last_sync.is_some());
+
+    let log = manager.read_sync_log(&SyncLogFilter { operation: Some("sync".to_string()), ..SyncLogFilter::default() }).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].direction.as_deref(), Some("pc-to-switch"));
+    assert_eq!(log[0].destination.as_deref(), Some(SWITCH));
+    assert!(log[0].destination_sha_before.is_some());
+    assert_ne!(log[0].destination_sha_before, log[0].destination_sha_after);
+}
+
+#[tokio::test]
+async fn undo_puts_the_overwritten_save_back() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(SWITCH, switch_save(200));
+    host.add_file(PC, pc_save(100));
+
+    manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    manager.undo_last_sync("hk", DEFAULT_PROFILE, false).await.unwrap();
+    assert_eq!(geo(&manager, SWITCH).await, 200);
+
+    let error = manager.undo_last_sync("hk", DEFAULT_PROFILE, false).await.unwrap_err();
+    assert_eq!(error.to_string(), "The last sync has already been undone");
+}
+
+#[tokio::test]
+async fn only_the_newest_undo_copy_is_kept() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(SWITCH, switch_save(200));
+    host.add_file(PC, pc_save(100));
+
+    manager.sync("hk", DEFAULT_PROFILE, Some("pc-to-switch".to_string()), false).await.unwrap();
+    manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap();
+
+    let undo_dir = std::path::Path::new("/home/player/.local/share/HollowSync/undo/hk");
+    assert_eq!(host.read_dir(undo_dir).unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn a_torn_log_line_does_not_swallow_the_next_entry() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, SWITCH, None).await;
+    host.add_file(SWITCH, switch_save(200));
+    host.add_file(PC, pc_save(100));
+    host.add_file("/home/player/.local/share/HollowSync/sync-log.jsonl", r#"{"timestamp":"2024-01-01T00:00:00Z","oper"#);
+
+    manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    let log = manager.read_sync_log(&SyncLogFilter::default()).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].outcome, "success");
+}
@@ -0,0 +1,177 @@
+use super::{geo, pc_save, setup, switch_save};
+use crate::host::Host;
+use crate::save_editor::{SaveEdit, SaveEditor};
+use crate::sync_log::SyncLogFilter;
+use serde_json::{json, Value};
+use std::path::Path;
+
+const SWITCH: &str = "/sdmc/saves/user1.json";
+const PC: &str = "/home/player/user1.dat";
+
+fn edited(player: Value, edit: SaveEdit) -> Result<Value, String> {
+    let mut editor = SaveEditor::new(json!({ "playerData": player })).unwrap();
+    editor.apply(&edit).map_err(|e| e.to_string())?;
+    Ok(editor.into_value()["playerData"].take())
+}
+
+#[tokio::test]
+async fn edits_keep_the_format_the_save_was_read_in() {
+    let (host, manager) = setup();
+    host.add_file(SWITCH, switch_save(100));
+    host.add_file(PC, pc_save(100));
+    let edits = [SaveEdit::Geo { value: 500 }];
+
+    manager.edit_save(SWITCH, "/sdmc/saves/user1.edited.json", &edits).await.unwrap();
+    let edited = host.read_to_string(Path::new("/sdmc/saves/user1.edited.json")).unwrap();
+    assert!(edited.starts_with('{'));
+
+    manager.edit_save(PC, "/home/player/user1.edited.dat", &edits).await.unwrap();
+    assert_eq!(host.read(Path::new("/home/player/user1.edited.dat")).unwrap()[..4], pc_save(100)[..4]);
+    assert_eq!(geo(&manager, "/home/player/user1.edited.dat").await, 500);
+    assert_eq!(geo(&manager, PC).await, 100);
+}
+
+#[tokio::test]
+async fn backup_zips_and_folders_are_never_edited_in_place() {
+    let (host, manager) = setup();
+    host.add_file("/sdmc/JKSV/Player 1/user1.dat", pc_save(100));
+    let edits = [SaveEdit::Geo { value: 500 }];
+
+    assert!(manager.is_backup_container("/sdmc/JKSV/Player 1"));
+    assert!(manager.edit_save("/sdmc/JKSV/Player 1", "/sdmc/JKSV/Player 1", &edits).await.is_err());
+
+    manager.edit_save("/sdmc/JKSV/Player 1", "/home/player/user1.dat", &edits).await.unwrap();
+    assert_eq!(geo(&manager, "/home/player/user1.dat").await, 500);
+    assert_eq!(geo(&manager, "/sdmc/JKSV/Player 1/user1.dat").await, 100);
+}
+
+#[tokio::test]
+async fn default_outputs_are_new_files_next_to_the_input() {
+    let (host, manager) = setup();
+    host.add_file(PC, pc_save(100));
+
+    assert_eq!(manager.sibling_output(PC, "edited"), "/home/player/user1.edited.dat");
+    host.add_file("/home/player/user1.edited.dat", pc_save(1));
+    assert_eq!(manager.sibling_output(PC, "edited"), "/home/player/user1.edited-2.dat");
+}
+
+#[tokio::test]
+async fn repair_only_writes_when_something_was_fixed() {
+    let (host, manager) = setup();
+    host.add_file(PC, pc_save(100));
+    let mut damaged = pc_save(100);
+    damaged.pop();
+    host.add_file("/home/player/user2.dat", damaged);
+
+    let report = manager.repair_save(PC, "/home/player/user1.repaired.dat").await.unwrap();
+    assert!(report.repairs.is_empty());
+    assert!(!host.exists(Path::new("/home/player/user1.repaired.dat")));
+
+    let report = manager.repair_save("/home/player/user2.dat", "/home/player/user2.repaired.dat").await.unwrap();
+    assert_eq!(report.repairs, vec!["Restored the BinaryFormatter trailer".to_string()]);
+    assert_eq!(geo(&manager, "/home/player/user2.repaired.dat").await, 100);
+}
+
+#[tokio::test]
+async fn conversions_write_and_log_the_sniffed_input() {
+    let (host, manager) = setup();
+    host.add_file(PC, pc_save(100));
+    host.add_file(SWITCH, switch_save(200));
+
+    let input = manager.sniffed(PC).await.unwrap();
+    manager.convert(PC, input, "/sdmc/saves/user2.dat", "pc-to-switch", false).await.unwrap();
+    assert!(host.read_to_string(Path::new("/sdmc/saves/user2.dat")).unwrap().starts_with('{'));
+
+    let input = manager.sniffed(SWITCH).await.unwrap();
+    manager.convert(SWITCH, input, "/home/player/user2.dat", "switch-to-pc", false).await.unwrap();
+    assert_eq!(geo(&manager, "/home/player/user2.dat").await, 200);
+
+    let log = manager.read_sync_log(&SyncLogFilter { operation: Some("convert".to_string()), ..SyncLogFilter::default() }).await.unwrap();
+    assert_eq!(log.len(), 2);
+    assert!(log.iter().all(|entry| entry.source_sha.is_some() && entry.destination_sha_after.is_some()));
+}
+
+#[test]
+fn geo_stays_in_range() {
+    assert_eq!(edited(json!({ "geo": 1 }), SaveEdit::Geo { value: 9_999_999 }).unwrap()["geo"], 9_999_999);
+    assert_eq!(edited(json!({ "geo": 1 }), SaveEdit::Geo { value: -1 }).unwrap_err(), "Geo must be between 0 and 9999999");
+}
+
+#[test]
+fn masks_refill_health_and_drop_partial_pieces() {
+    let player = json!({ "maxHealthCap": 9, "maxHealthBase": 5, "maxHealth": 5, "health": 2, "prevHealth": 2, "heartPieces": 3 });
+    let player = edited(player, SaveEdit::Masks { value: 7 }).unwrap();
+    assert_eq!(player, json!({ "maxHealthCap": 9, "maxHealthBase": 7, "maxHealth": 7, "health": 7, "prevHealth": 7, "heartPieces": 0 }));
+
+    let error = edited(json!({ "maxHealthCap": 6 }), SaveEdit::Masks { value: 7 }).unwrap_err();
+    assert_eq!(error, "Masks must be between 1 and 6");
+}
+
+#[test]
+fn soul_vessels_cap_the_reserve() {
+    let player = edited(json!({ "MPReserveMax": 99, "MPReserve": 80, "vesselFragments": 2 }), SaveEdit::SoulVessels { value: 1 }).unwrap();
+    assert_eq!(player, json!({ "MPReserveMax": 33, "MPReserve": 33, "vesselFragments": 0 }));
+    assert!(edited(json!({}), SaveEdit::SoulVessels { value: 4 }).is_err());
+}
+
+#[test]
+fn unowning_an_equipped_charm_frees_its_notches() {
+    let player = json!({
+        "gotCharm_1": true, "equippedCharm_1": true, "charmCost_1": 2,
+        "gotCharm_2": true, "equippedCharm_2": true, "charmCost_2": 3,
+        "gotCharm_3": false, "equippedCharm_3": false, "charmCost_3": 1,
+        "equippedCharms": [1, 2], "charmsOwned": 2, "charmSlots": 4, "charmSlotsFilled": 5, "overcharmed": true
+    });
+
+    let player = edited(player, SaveEdit::CharmOwned { charm: 2, owned: false }).unwrap();
+    assert_eq!(player["gotCharm_2"], false);
+    assert_eq!(player["equippedCharm_2"], false);
+    assert_eq!(player["equippedCharms"], json!([1]));
+    assert_eq!(player["charmsOwned"], 1);
+    assert_eq!(player["charmSlotsFilled"], 2);
+    assert_eq!(player["overcharmed"], false);
+
+    let player = edited(player, SaveEdit::CharmOwned { charm: 3, owned: true }).unwrap();
+    assert_eq!(player["charmsOwned"], 2);
+    assert_eq!(player["equippedCharm_3"], false);
+    assert_eq!(edited(player, SaveEdit::CharmOwned { charm: 40, owned: true }).unwrap_err(), "Unknown charm: 40");
+}
+
+#[test]
+fn charm_notches_are_counted_from_the_flags_without_an_equipped_list() {
+    let player = json!({
+        "gotCharm_1": true, "equippedCharm_1": true, "charmCost_1": 2,
+        "gotCharm_2": true, "equippedCharm_2": true, "charmCost_2": 3,
+        "charmSlots": 3, "charmSlotsFilled": 5, "overcharmed": true
+    });
+    let player = edited(player, SaveEdit::CharmOwned { charm: 1, owned: false }).unwrap();
+    assert_eq!(player["charmSlotsFilled"], 3);
+    assert_eq!(player["overcharmed"], false);
+}
+
+#[test]
+fn unowned_tools_are_hidden_again() {
+    let player = json!({ "Tools": { "savedData": [{ "Name": "Straight Pin", "Data": { "IsUnlocked": true, "IsHidden": false } }] } });
+    let player = edited(player, SaveEdit::ToolOwned { tool: "Straight Pin".to_string(), owned: false }).unwrap();
+    assert_eq!(player["Tools"]["savedData"][0]["Data"], json!({ "IsUnlocked": false, "IsHidden": true }));
+
+    let error = edited(player, SaveEdit::ToolOwned { tool: "Sting Shard".to_string(), owned: true }).unwrap_err();
+    assert_eq!(error, "Unknown tool: Sting Shard");
+    assert_eq!(edited(json!({}), SaveEdit::ToolOwned { tool: "Straight Pin".to_string(), owned: true }).unwrap_err(), "Save has no tool list");
+}
+
+#[test]
+fn respawn_benches_spawn_sitting() {
+    let bench = SaveEdit::RespawnBench { scene: "Town".to_string(), marker: "RestBench".to_string() };
+    let player = edited(json!({ "respawnType": 0, "hazardRespawnFacingRight": false }), bench).unwrap();
+    assert_eq!(player, json!({ "respawnScene": "Town", "respawnMarkerName": "RestBench", "respawnType": 1, "hazardRespawnFacingRight": true }));
+
+    let unnamed = SaveEdit::RespawnBench { scene: "Town".to_string(), marker: String::new() };
+    assert!(edited(json!({}), unnamed).is_err());
+}
+
+#[test]
+fn steel_soul_sets_the_permadeath_mode() {
+    assert_eq!(edited(json!({ "permadeathMode": 2 }), SaveEdit::SteelSoul { enabled: false }).unwrap()["permadeathMode"], 0);
+    assert_eq!(edited(json!({}), SaveEdit::SteelSoul { enabled: true }).unwrap()["permadeathMode"], 1);
+}
@@ -0,0 +1,199 @@
+use crate::ftp::{self, FtpTarget};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+const FOLDER: &str = "/JKSV/Hollow Knight";
+
+// path -> contents, None for a folder
+type Files = Arc<Mutex<BTreeMap<String, Option<Vec<u8>>>>>;
+
+// a single-user server on loopback in passive mode; `mlsd` off plays an older homebrew
+// server that can only list names
+struct Server {
+    port: u16,
+    files: Files,
+}
+
+impl Server {
+    fn start(mlsd: bool) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let files = Files::default();
+        files.lock().unwrap().insert(FOLDER.to_string(), None);
+        let served = files.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                Self::session(stream, &served, mlsd);
+            }
+        });
+        Self { port, files }
+    }
+
+    fn target(&self, password: &str) -> FtpTarget {
+        let url = format!("ftp://127.0.0.1:{}", self.port);
+        FtpTarget::from_url(&url, FOLDER, Some("knight".to_string()), Some(password.to_string())).unwrap()
+    }
+
+    fn add_folder(&self, path: &str) {
+        self.files.lock().unwrap().insert(path.to_string(), None);
+    }
+
+    fn children(files: &Files, directory: &str) -> Vec<(String, bool)> {
+        let prefix = format!("{}/", directory);
+        files
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(path, data)| {
+                let name = path.strip_prefix(&prefix)?;
+                (!name.contains('/')).then(|| (name.to_string(), data.is_none()))
+            })
+            .collect()
+    }
+
+    fn session(stream: TcpStream, files: &Files, mlsd: bool) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut control = stream;
+        let reply = |control: &mut TcpStream, line: &str| control.write_all(format!("{}\r\n", line).as_bytes()).unwrap();
+        reply(&mut control, "220 stand-in ready");
+
+        let mut passive: Option<TcpListener> = None;
+        let send = |control: &mut TcpStream, passive: &mut Option<TcpListener>, data: &[u8]| {
+            let (mut connection, _) = passive.take().unwrap().accept().unwrap();
+            reply(control, "150 opening data connection");
+            connection.write_all(data).unwrap();
+            drop(connection);
+            reply(control, "226 transfer complete");
+        };
+
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let line = line.trim_end();
+            let (verb, argument) = line.split_once(' ').unwrap_or((line, ""));
+            match verb {
+                "USER" => reply(&mut control, "331 password please"),
+                "PASS" if argument == "hornet" => reply(&mut control, "230 logged in"),
+                "PASS" => reply(&mut control, "530 login incorrect"),
+                "TYPE" => reply(&mut control, "200 binary"),
+                "PASV" => {
+                    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                    let port = listener.local_addr().unwrap().port();
+                    passive = Some(listener);
+                    // advertise 0.0.0.0 like some homebrew servers, the client must ignore it
+                    reply(&mut control, &format!("227 Entering Passive Mode (0,0,0,0,{},{})", port / 256, port % 256));
+                }
+                "MKD" => {
+                    files.lock().unwrap().insert(argument.to_string(), None);
+                    reply(&mut control, "257 created");
+                }
+                "MLSD" if mlsd => {
+                    let listing: String = Self::children(files, argument)
+                        .into_iter()
+                        .map(|(name, is_dir)| format!("type={};modify=20240601120000; {}\r\n", if is_dir { "dir" } else { "file" }, name))
+                        .collect();
+                    send(&mut control, &mut passive, listing.as_bytes());
+                }
+                "NLST" => {
+                    let listing: String = Self::children(files, argument)
+                        .into_iter()
+                        .map(|(name, _)| format!("{}/{}\r\n", argument, name))
+                        .collect();
+                    send(&mut control, &mut passive, listing.as_bytes());
+                }
+                "RETR" => match files.lock().unwrap().get(argument).cloned().flatten() {
+                    Some(data) => send(&mut control, &mut passive, &data),
+                    None => reply(&mut control, "550 no such file"),
+                },
+                "STOR" => {
+                    let (mut connection, _) = passive.take().unwrap().accept().unwrap();
+                    reply(&mut control, "150 ready to receive");
+                    let mut data = Vec::new();
+                    connection.read_to_end(&mut data).unwrap();
+                    files.lock().unwrap().insert(argument.to_string(), Some(data));
+                    reply(&mut control, "226 stored");
+                }
+                "QUIT" => {
+                    reply(&mut control, "221 bye");
+                    return;
+                }
+                _ => reply(&mut control, "500 unknown command"),
+            }
+        }
+    }
+}
+
+#[test]
+fn passive_replies_give_the_data_port() {
+    assert_eq!(ftp::parse_pasv_port("Entering Passive Mode (192,168,1,20,195,80).").unwrap(), 50000);
+    assert_eq!(ftp::parse_pasv_port("=(0,0,0,0,0,21)").unwrap(), 21);
+
+    for malformed in ["Entering Passive Mode", "(192,168,1,20,195)", "(192,168,1,20,300,1)", "(a,b,c,d,e,f)", "(1,2,3,4,5,6"] {
+        assert!(ftp::parse_pasv_port(malformed).is_err(), "{}", malformed);
+    }
+}
+
+#[test]
+fn name_only_listings_take_anything_but_zips_and_saves_for_folders() {
+    let listing = "/JKSV/Hollow Knight/.\r\n/JKSV/Hollow Knight/..\r\n/JKSV/Hollow Knight/Hornet - 2024.06.01 @ 12.00.00\r\nbackup.ZIP\r\nuser1.dat\r\n\r\n";
+    let entries: Vec<(String, bool)> = ftp::parse_nlst(listing).into_iter().map(|entry| (entry.name, entry.is_dir)).collect();
+    assert_eq!(
+        entries,
+        vec![
+            ("Hornet - 2024.06.01 @ 12.00.00".to_string(), true),
+            ("backup.ZIP".to_string(), false),
+            ("user1.dat".to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn name_stamps_are_read_in_the_local_zone() {
+    let name = "Hornet - 2024.06.01 @ 12.00.00";
+    let tokyo = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+    let stamped = ftp::backup_name_time_in(name, &tokyo).unwrap();
+    assert_eq!(stamped.to_rfc3339(), "2024-06-01T03:00:00+00:00");
+
+    // a backup stamped at noon in tokyo is older than a file modified at 10:00 utc the same day
+    let modified = chrono::DateTime::parse_from_rfc3339("2024-06-01T10:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    assert!(stamped < modified);
+}
+
+#[test]
+fn uploads_download_back_through_the_passive_data_connection() {
+    let server = Server::start(true);
+    let backup = ftp::join_path(FOLDER, "Hornet - 2024.06.01 @ 12.00.00");
+
+    let mut session = server.target("hornet").connect().unwrap();
+    session.make_dir(&backup).unwrap();
+    session.upload(&ftp::join_path(&backup, "user1.dat"), b"encrypted save").unwrap();
+    let listed: Vec<(String, bool)> = session.list(&backup).unwrap().into_iter().map(|entry| (entry.name, entry.is_dir)).collect();
+    assert_eq!(listed, vec![("user1.dat".to_string(), false)]);
+    assert_eq!(session.download(&ftp::join_path(&backup, "user1.dat")).unwrap(), b"encrypted save");
+    assert!(session.download(&ftp::join_path(&backup, "user2.dat")).is_err());
+    session.quit();
+
+    assert!(server.target("wrong").connect().is_err());
+}
+
+#[test]
+fn servers_without_mlsd_are_listed_by_name() {
+    let server = Server::start(false);
+    server.add_folder(&ftp::join_path(FOLDER, "Hornet - 2024.06.01 @ 12.00.00"));
+    server.add_folder(&ftp::join_path(FOLDER, "Hornet - 2024.08.01 @ 09.30.00"));
+
+    let mut session = server.target("hornet").connect().unwrap();
+    session.upload(&ftp::join_path(FOLDER, "notes.txt"), b"hello").unwrap();
+    let entries = session.list(FOLDER).unwrap();
+    session.quit();
+
+    assert!(entries.iter().all(|entry| entry.modified.is_none()));
+    // without mtimes the newest backup comes from the stamp jksv puts in the name; notes.txt
+    // is mistaken for a folder but has no stamp, so it never wins
+    assert!(entries.iter().find(|entry| entry.name == "notes.txt").unwrap().is_dir);
+    assert_eq!(ftp::newest_backup(entries).unwrap().name, "Hornet - 2024.08.01 @ 09.30.00");
+}
@@ -0,0 +1,221 @@
+use super::{configure, geo, pc_save, setup};
+use crate::host::Host;
+use crate::progress::{CancelToken, Progress, CANCELLED};
+use crate::sync_config::DEFAULT_PROFILE;
+use crate::sync_log::SyncLogFilter;
+use std::io::Write;
+use std::path::Path;
+
+const PC: &str = "/home/player/AppData/LocalLow/Team Cherry/Hollow Knight/user1.dat";
+const JKSV: &str = "/sdmc/JKSV/Hollow Knight/Player 1";
+const ZIP: &str = "/sdmc/JKSV/Hollow Knight/backup.zip";
+
+fn zip_of(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+        if name.ends_with('/') {
+            writer.add_directory(*name, zip::write::FileOptions::default()).unwrap();
+        } else {
+            writer.start_file(*name, zip::write::FileOptions::default()).unwrap();
+            writer.write_all(data).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[tokio::test]
+async fn pc_save_is_copied_into_the_backup_folder_under_the_mapped_slot() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, JKSV, Some(2)).await;
+    host.create_dir_all(Path::new(JKSV)).unwrap();
+    host.add_file(PC, pc_save(100));
+
+    let plan = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(plan.direction, "pc-to-switch");
+    assert_eq!(plan.layout, "JKSV backup folder holding user2.dat");
+
+    manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    let copied = format!("{}/user2.dat", JKSV);
+    assert_eq!(host.read(Path::new(&copied)).unwrap(), pc_save(100));
+    assert!(!host.exists(&Path::new(JKSV).join("user1.dat")));
+}
+
+#[tokio::test]
+async fn a_folder_path_that_does_not_exist_yet_is_created() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, JKSV, None).await;
+    host.add_file(PC, pc_save(100));
+
+    manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(geo(&manager, &format!("{}/user1.dat", JKSV)).await, 100);
+}
+
+#[tokio::test]
+async fn the_mapped_slot_is_picked_from_a_backup_folder() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, JKSV, Some(2)).await;
+    host.add_file(format!("{}/user1.dat", JKSV), pc_save(1));
+    host.add_file(format!("{}/user2.dat", JKSV), pc_save(2));
+    host.add_file(PC, pc_save(100));
+
+    manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap();
+    assert_eq!(geo(&manager, PC).await, 2);
+}
+
+#[tokio::test]
+async fn a_backup_without_the_mapped_slot_leaves_the_pc_save_alone() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, ZIP, Some(3)).await;
+    host.add_file(ZIP, zip_of(&[("user1.dat", &pc_save(1)), ("user2.dat", &pc_save(2))]));
+    host.add_file(PC, pc_save(100));
+
+    let error = manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap_err();
+    assert_eq!(error.to_string(), "The backup has no user3.dat, the save for the profile's Switch slot");
+    assert_eq!(geo(&manager, PC).await, 100);
+}
+
+#[tokio::test]
+async fn a_backup_zip_is_extracted_and_the_scratch_folder_removed() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, ZIP, None).await;
+    host.add_file(ZIP, zip_of(&[("HollowSync/user1.dat", &pc_save(300))]));
+
+    let plan = manager.plan_sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(plan.direction, "switch-to-pc");
+    assert_eq!(plan.reads, vec![format!("{} (user1.dat inside the zip)", ZIP)]);
+
+    manager.sync("hk", DEFAULT_PROFILE, None, false).await.unwrap();
+    assert_eq!(geo(&manager, PC).await, 300);
+    let pc_dir = Path::new(PC).parent().unwrap();
+    assert_eq!(host.read_dir(pc_dir).unwrap(), vec![Path::new(PC).to_path_buf()]);
+}
+
+#[tokio::test]
+async fn extraction_only_keeps_save_files() {
+    let (host, manager) = setup();
+    let data = pc_save(1);
+    host.add_file(ZIP, zip_of(&[("saves/", b""), ("saves/user1.dat", &data), ("readme.txt", b"hello")]));
+
+    let extracted = manager.extract_backup(ZIP, "/home/player/extracted").await.unwrap();
+    assert_eq!(extracted, vec!["/home/player/extracted/user1.dat".to_string()]);
+    assert_eq!(host.read_dir(Path::new("/home/player/extracted")).unwrap().len(), 1);
+    assert_eq!(manager.read_save_bytes(ZIP).await.unwrap(), data);
+}
+
+#[tokio::test]
+async fn a_zip_without_saves_leaves_the_pc_save_alone() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, ZIP, None).await;
+    host.add_file(ZIP, zip_of(&[("readme.txt", b"hello")]));
+    host.add_file(PC, pc_save(100));
+
+    let error = manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap_err();
+    assert_eq!(error.to_string(), "No save files found in backup");
+    assert_eq!(geo(&manager, PC).await, 100);
+    assert!(!host.exists(&Path::new(PC).parent().unwrap().join("temp-extract")));
+}
+
+#[tokio::test]
+async fn restore_points_go_over_under_their_slot_name() {
+    let (host, manager) = setup();
+    let pc_dir = Path::new(PC).parent().unwrap();
+    configure(&manager, "hk", PC, JKSV, None).await;
+    host.create_dir_all(Path::new(JKSV)).unwrap();
+    host.add_file(pc_dir.join("user1_1.4.3.2.dat"), pc_save(14));
+    host.add_file(pc_dir.join("user3.dat.bak1"), pc_save(30));
+
+    manager.sync_from_backup("hk", DEFAULT_PROFILE, &pc_dir.join("user1_1.4.3.2.dat").to_string_lossy()).await.unwrap();
+    manager.sync_from_backup("hk", DEFAULT_PROFILE, &pc_dir.join("user3.dat.bak1").to_string_lossy()).await.unwrap();
+
+    let mut names: Vec<String> = host
+        .read_dir(Path::new(JKSV))
+        .unwrap()
+        .iter()
+        .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["user1.dat", "user3.dat"]);
+    assert_eq!(geo(&manager, &format!("{}/user3.dat", JKSV)).await, 30);
+}
+
+#[tokio::test]
+async fn restore_points_follow_the_mapped_slot() {
+    let (host, manager) = setup();
+    let backup = Path::new(PC).parent().unwrap().join("user1.dat.bak");
+    configure(&manager, "hk", PC, JKSV, Some(2)).await;
+    host.add_file(&backup, pc_save(10));
+
+    manager.sync_from_backup("hk", DEFAULT_PROFILE, &backup.to_string_lossy()).await.unwrap();
+    assert_eq!(geo(&manager, &format!("{}/user2.dat", JKSV)).await, 10);
+
+    host.add_file("/home/player/notes.dat", pc_save(1));
+    assert!(manager.sync_from_backup("hk", DEFAULT_PROFILE, "/home/player/notes.dat").await.is_err());
+}
+
+#[tokio::test]
+async fn restore_point_syncs_log_a_profile_that_fails_to_load() {
+    let (host, manager) = setup();
+    let backup = Path::new(PC).parent().unwrap().join("user1.dat.bak");
+    host.add_file(&backup, pc_save(10));
+
+    assert!(manager.sync_from_backup("hk", "../escape", &backup.to_string_lossy()).await.is_err());
+    let log = manager.read_sync_log(&SyncLogFilter::default()).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!((log[0].profile.as_deref(), log[0].outcome.as_str()), (Some("../escape"), "error"));
+}
+
+#[tokio::test]
+async fn a_failed_sync_keeps_the_last_one_undoable() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, ZIP, None).await;
+    host.add_file(PC, pc_save(100));
+    host.add_file(ZIP, zip_of(&[("user1.dat", &pc_save(300))]));
+
+    manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap();
+    host.add_file(ZIP, zip_of(&[("readme.txt", b"hello")]));
+    assert!(manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.is_err());
+
+    manager.undo_last_sync("hk", DEFAULT_PROFILE, false).await.unwrap();
+    assert_eq!(geo(&manager, PC).await, 100);
+}
+
+#[tokio::test]
+async fn restore_point_syncs_can_be_undone() {
+    let (host, manager) = setup();
+    let backup = Path::new(PC).parent().unwrap().join("user1.dat.bak");
+    configure(&manager, "hk", PC, JKSV, None).await;
+    host.add_file(format!("{}/user1.dat", JKSV), pc_save(100));
+    host.add_file(&backup, pc_save(10));
+
+    let result = manager.sync_from_backup("hk", DEFAULT_PROFILE, &backup.to_string_lossy()).await.unwrap();
+    assert!(result.undoable);
+    assert_eq!(geo(&manager, &format!("{}/user1.dat", JKSV)).await, 10);
+
+    manager.undo_last_sync("hk", DEFAULT_PROFILE, false).await.unwrap();
+    assert_eq!(geo(&manager, &format!("{}/user1.dat", JKSV)).await, 100);
+}
+
+#[tokio::test]
+async fn cancelling_mid_extraction_keeps_the_save_and_the_last_undo() {
+    let (host, manager) = setup();
+    configure(&manager, "hk", PC, ZIP, None).await;
+    host.add_file(PC, pc_save(100));
+    host.add_file(ZIP, zip_of(&[("user1.dat", &pc_save(300))]));
+    manager.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap();
+
+    host.add_file(ZIP, zip_of(&[("user1.dat", &pc_save(500))]));
+    let cancel = CancelToken::default();
+    let token = cancel.clone();
+    let cancelling = manager.clone().with_progress(Progress::new("sync-1", "sync", cancel, move |event| {
+        if event.phase == "extracting" {
+            token.cancel();
+        }
+    }));
+    let error = cancelling.sync("hk", DEFAULT_PROFILE, Some("switch-to-pc".to_string()), false).await.unwrap_err();
+    assert_eq!(error.to_string(), CANCELLED);
+    assert_eq!(geo(&manager, PC).await, 300);
+    assert!(!host.exists(&Path::new(PC).parent().unwrap().join("temp-extract")));
+
+    manager.undo_last_sync("hk", DEFAULT_PROFILE, false).await.unwrap();
+    assert_eq!(geo(&manager, PC).await, 100);
+}
@@ -0,0 +1,60 @@
+// end to end checks of SaveManager against an in-memory host, so nothing here reads or
+// writes the real home folder, Steam install or config directory; the remote targets are
+// checked against small stand-in servers on loopback
+mod cloud;
+mod config;
+mod detection;
+mod direction;
+mod editing;
+mod ftp;
+mod jksv;
+mod scan_bench;
+mod validation;
+mod watcher;
+mod webdav;
+
+use crate::crypto::SaveCrypto;
+use crate::host::MemoryHost;
+use crate::save_manager::SaveManager;
+use crate::sync_config::{SyncConfig, DEFAULT_PROFILE};
+use std::sync::Arc;
+
+pub const HOME: &str = "/home/player";
+
+fn save_json(geo: u32) -> String {
+    format!(r#"{{"playerData":{{"version":"1.5.78.11833","geo":{},"maxHealth":5}},"sceneData":{{}}}}"#, geo)
+}
+
+// an encrypted pc save holding `geo`, which the tests use to tell saves apart
+pub fn pc_save(geo: u32) -> Vec<u8> {
+    SaveCrypto::new().switch_to_pc(&save_json(geo)).unwrap()
+}
+
+pub fn switch_save(geo: u32) -> String {
+    save_json(geo)
+}
+
+pub fn setup() -> (Arc<MemoryHost>, SaveManager) {
+    setup_with(MemoryHost::new())
+}
+
+pub fn setup_with(host: MemoryHost) -> (Arc<MemoryHost>, SaveManager) {
+    let host = Arc::new(host);
+    (host.clone(), SaveManager::with_host(host))
+}
+
+// history needs git and a real disk, so test profiles leave it off
+pub async fn configure(manager: &SaveManager, game: &str, pc_save: &str, switch_save: &str, switch_slot: Option<u8>) {
+    let config = SyncConfig {
+        pc_save: pc_save.to_string(),
+        switch_save: switch_save.to_string(),
+        switch_slot,
+        git_history: false,
+        ..SyncConfig::default()
+    };
+    manager.save_config(game, DEFAULT_PROFILE, &config).await.unwrap();
+}
+
+pub async fn geo(manager: &SaveManager, path: &str) -> u64 {
+    manager.load_save(path).await.unwrap()["playerData"]["geo"].as_u64().unwrap()
+}
@@ -0,0 +1,86 @@
+// times save detection over a synthetic tree on the real disk; ignored by default, run it
+// after changing the scanner with `cargo test --release scan_bench -- --ignored --nocapture`
+use crate::crypto::SaveCrypto;
+use crate::save_manager::SaveManager;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const FOLDERS: usize = 16;
+const RUNS: usize = 5;
+
+// one search path per folder, each shaped like a silksong save folder: two steam id
+// subfolders with all four slots plus an update copy and a .bak for every slot
+fn build_tree(root: &Path, folders: usize) -> Vec<(String, PathBuf)> {
+    let crypto = SaveCrypto::new();
+    let save = crypto.switch_to_pc(r#"{"playerData":{"version":"1.0.28324","geo":1200,"maxHealth":7},"sceneData":{}}"#).unwrap();
+
+    let mut search_paths = Vec::new();
+    for folder in 0..folders {
+        let base = root.join(format!("folder-{}", folder));
+        for user in 0..2 {
+            let steam_dir = base.join(format!("7656119800{:07}", folder * 2 + user));
+            fs::create_dir_all(&steam_dir).unwrap();
+            for slot in 1..=4 {
+                fs::write(steam_dir.join(format!("user{}.dat", slot)), &save).unwrap();
+                fs::write(steam_dir.join(format!("user{}_1.0.28324.dat", slot)), &save).unwrap();
+                fs::write(steam_dir.join(format!("user{}.dat.bak1", slot)), &save).unwrap();
+            }
+        }
+        search_paths.push(("silksong".to_string(), base));
+    }
+    search_paths
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+fn millis(time: Duration) -> f64 {
+    time.as_secs_f64() * 1000.0
+}
+
+// scanning the folders one by one, all at once with a cold summary cache, and all at
+// once again with the cache warm
+#[tokio::test(flavor = "multi_thread")]
+#[ignore]
+async fn scan_bench() {
+    let root = std::env::temp_dir().join(format!("hollow-sync-bench-{}", std::process::id()));
+    let search_paths = build_tree(&root, FOLDERS);
+
+    let mut one_at_a_time = Vec::new();
+    let mut all_at_once = Vec::new();
+    let mut cached = Vec::new();
+    let mut found = Vec::new();
+    for _ in 0..RUNS {
+        let manager = SaveManager::new();
+        let started = Instant::now();
+        for search_path in &search_paths {
+            manager.detect_saves_in(vec![search_path.clone()]).await.unwrap();
+        }
+        one_at_a_time.push(started.elapsed());
+
+        let manager = SaveManager::new();
+        let started = Instant::now();
+        manager.detect_saves_in(search_paths.clone()).await.unwrap();
+        all_at_once.push(started.elapsed());
+
+        let started = Instant::now();
+        found = manager.detect_saves_in(search_paths.clone()).await.unwrap();
+        cached.push(started.elapsed());
+    }
+    let _ = fs::remove_dir_all(&root);
+
+    assert_eq!(found.len(), FOLDERS * 2 * 4);
+    println!(
+        "Scanned {} folders ({} slots, {} restore points), median of {} runs:",
+        FOLDERS,
+        found.len(),
+        found.iter().map(|save| save.restore_points.len()).sum::<usize>(),
+        RUNS
+    );
+    println!("  one folder at a time      {:>8.1} ms", millis(median(one_at_a_time)));
+    println!("  all folders at once       {:>8.1} ms", millis(median(all_at_once)));
+    println!("  again, summaries cached   {:>8.1} ms", millis(median(cached)));
+}
@@ -0,0 +1,149 @@
+use crate::crypto::SaveCrypto;
+use crate::save_validate::{self, Finding};
+use base64::{engine::general_purpose, Engine as _};
+use serde_json::{json, Value};
+
+// a save every rule is happy with, which each case below breaks in one place
+fn healthy() -> Value {
+    json!({
+        "playerData": {
+            "version": "1.5.78.11833", "playTime": 3600.0, "geo": 100, "maxHealth": 5, "maxHealthBase": 5, "health": 5,
+            "respawnScene": "Town", "respawnMarkerName": "RestBench", "scenesVisited": ["Town", "Crossroads_01"],
+            "charmSlots": 3, "overcharmed": false, "equippedCharms": [1],
+            "gotCharm_1": true, "equippedCharm_1": true, "charmCost_1": 1,
+            "heartPieces": 2, "vesselFragments": 1,
+            "defeatedDungDefender": true, "killedDungDefender": true,
+            "openedCityGate": true, "hasCityKey": true
+        },
+        "sceneData": { "persistentBoolItems": [{ "sceneName": "Crossroads_01", "id": "Chest" }] }
+    })
+}
+
+fn findings(save: &Value) -> Vec<Finding> {
+    let mut findings = save_validate::check_structure(save);
+    findings.extend(save_validate::check_consistency(save));
+    findings.extend(save_validate::check_schema(save));
+    findings
+}
+
+fn codes_after(edit: impl FnOnce(&mut Value)) -> Vec<String> {
+    let mut save = healthy();
+    edit(&mut save);
+    findings(&save).into_iter().map(|finding| finding.code).collect()
+}
+
+fn inspected_codes(data: &[u8]) -> Vec<String> {
+    let (report, _) = save_validate::inspect(&SaveCrypto::new(), data);
+    report.findings.into_iter().map(|finding| finding.code).collect()
+}
+
+fn pc_file(payload: &[u8]) -> Vec<u8> {
+    SaveCrypto::add_save_header(payload)
+}
+
+fn encrypted_base64(json: &str) -> String {
+    general_purpose::STANDARD.encode(SaveCrypto::new().encrypt(json.as_bytes()))
+}
+
+#[test]
+fn a_healthy_save_has_no_findings() {
+    assert_eq!(findings(&healthy()), Vec::new());
+    let (report, _) = save_validate::inspect(&SaveCrypto::new(), &SaveCrypto::new().switch_to_pc(&healthy().to_string()).unwrap());
+    assert!(report.is_valid());
+    assert!(report.findings.is_empty() && report.repairs.is_empty());
+}
+
+#[test]
+fn each_rule_flags_the_save_that_breaks_it() {
+    // (rule, field set through a json pointer, value that breaks it)
+    let cases = [
+        ("missing-player-data", "/playerData", json!(null)),
+        ("missing-top-level-key", "/sceneData", json!(null)),
+        ("value-out-of-range", "/playerData/charmSlots", json!(12)),
+        ("equipped-charm-not-owned", "/playerData/gotCharm_1", json!(false)),
+        ("charm-notches-exceeded", "/playerData/charmCost_1", json!(4)),
+        ("fragment-count-out-of-range", "/playerData/heartPieces", json!(4)),
+        ("negative-currency", "/playerData/geo", json!(-5)),
+        ("health-above-max", "/playerData/health", json!(9)),
+        ("respawn-scene-not-visited", "/playerData/respawnScene", json!("Abyss_01")),
+        ("boss-flag-mismatch", "/playerData/killedDungDefender", json!(false)),
+        ("door-without-key", "/playerData/hasCityKey", json!(false)),
+        ("unvisited-scene-reference", "/sceneData/persistentBoolItems/0/sceneName", json!("Abyss_01")),
+        ("wrong-type", "/playerData/geo", json!("100")),
+    ];
+
+    for (code, pointer, value) in cases {
+        let codes = codes_after(|save| *save.pointer_mut(pointer).unwrap() = value);
+        assert!(codes.contains(&code.to_string()), "{} not in {:?}", code, codes);
+    }
+    let codes = codes_after(|save| {
+        save["playerData"].as_object_mut().unwrap().remove("playTime");
+    });
+    assert!(codes.contains(&"missing-field".to_string()));
+    assert_eq!(save_validate::check_schema(&json!([healthy()]))[0].code, "not-an-object");
+}
+
+#[test]
+fn overcharming_on_purpose_is_not_flagged() {
+    let codes = codes_after(|save| {
+        save["playerData"]["charmCost_1"] = json!(4);
+        save["playerData"]["overcharmed"] = json!(true);
+    });
+    assert!(!codes.contains(&"charm-notches-exceeded".to_string()));
+}
+
+#[test]
+fn a_used_key_explains_an_opened_door() {
+    let codes = codes_after(|save| {
+        save["playerData"]["hasCityKey"] = json!(false);
+        save["playerData"]["usedCityKey"] = json!(true);
+    });
+    assert!(!codes.contains(&"door-without-key".to_string()));
+}
+
+#[test]
+fn boss_kills_missing_from_the_journal_are_repaired() {
+    let mut save = healthy();
+    save["playerData"]["killedDungDefender"] = json!(false);
+    let (report, repaired) = save_validate::inspect(&SaveCrypto::new(), save.to_string().as_bytes());
+
+    let mismatch = report.findings.iter().find(|finding| finding.code == "boss-flag-mismatch").unwrap();
+    assert_eq!(mismatch.field.as_deref(), Some("killedDungDefender"));
+    assert_eq!(report.repairs, vec!["Set killedDungDefender to match defeatedDungDefender".to_string()]);
+    assert_eq!(repaired.unwrap()["playerData"]["killedDungDefender"], true);
+}
+
+#[test]
+fn each_damaged_layer_of_a_pc_save_is_flagged() {
+    let json = healthy().to_string();
+    let base64 = encrypted_base64(&json);
+    let header = &pc_file(b"")[..22];
+
+    let mut no_trailer = pc_file(base64.as_bytes());
+    no_trailer.pop();
+    let mut extra_bytes = SaveCrypto::new().encrypt(json.as_bytes());
+    extra_bytes.extend_from_slice(&[1, 2, 3, 4, 5]);
+    // the last block of a padded json would hold only padding; dropping it leaves the
+    // final byte a space from the json instead of a pkcs7 count
+    let mut unpadded = json.clone().into_bytes();
+    unpadded.resize(unpadded.len().div_ceil(16) * 16, b' ');
+    let mut bad_padding = SaveCrypto::new().encrypt(&unpadded);
+    bad_padding.truncate(unpadded.len());
+
+    let cases: Vec<(&str, Vec<u8>)> = vec![
+        ("missing-header", b"not a save".to_vec()),
+        ("missing-trailer", no_trailer),
+        ("invalid-header", header.to_vec()),
+        ("bad-base64-padding", pc_file(format!("{}=", base64).as_bytes())),
+        ("invalid-base64", pc_file(b"!!!!")),
+        ("truncated-ciphertext", pc_file(general_purpose::STANDARD.encode(extra_bytes).as_bytes())),
+        ("bad-padding", pc_file(general_purpose::STANDARD.encode(bad_padding).as_bytes())),
+        ("invalid-json", b"{{".to_vec()),
+        ("damaged-json", format!("{}trailing", json).into_bytes()),
+    ];
+
+    for (code, data) in cases {
+        let codes = inspected_codes(&data);
+        assert!(codes.contains(&code.to_string()), "{} not in {:?}", code, codes);
+    }
+}
@@ -0,0 +1,38 @@
+use super::{pc_save, setup};
+use crate::watcher::{changed_since, watch_target};
+use chrono::{DateTime, Utc};
+use notify::RecursiveMode;
+use std::path::PathBuf;
+use std::time::Duration;
+
+const PC: &str = "/home/player/saves/user1.dat";
+const JKSV: &str = "/sdmc/JKSV/Hollow Knight/Player 1";
+
+#[test]
+fn saves_are_watched_through_their_folder_and_jksv_folders_recursively() {
+    let (host, _) = setup();
+    host.add_file(PC, pc_save(100));
+    host.add_file(format!("{}/user1.dat", JKSV), pc_save(100));
+
+    assert_eq!(watch_target(host.as_ref(), PC, false), Some((PathBuf::from("/home/player/saves"), RecursiveMode::NonRecursive)));
+    assert_eq!(watch_target(host.as_ref(), JKSV, true), Some((PathBuf::from(JKSV), RecursiveMode::Recursive)));
+    assert_eq!(watch_target(host.as_ref(), "/media/sd/user1.json", true), None);
+}
+
+#[test]
+fn a_newer_file_anywhere_in_a_jksv_folder_is_a_change() {
+    let (host, _) = setup();
+    host.add_file(format!("{}/user1.dat", JKSV), pc_save(100));
+    host.add_file(PC, pc_save(100));
+    let last_sync = DateTime::<Utc>::from(host.now() + Duration::from_secs(60));
+
+    assert!(!changed_since(host.as_ref(), JKSV, Some(last_sync)));
+    assert!(!changed_since(host.as_ref(), PC, Some(last_sync)));
+    assert!(changed_since(host.as_ref(), PC, None));
+    assert!(!changed_since(host.as_ref(), "/home/player/saves/user2.dat", None));
+
+    let nested = format!("{}/HollowSync/user1.dat", JKSV);
+    host.add_file(&nested, pc_save(200));
+    host.set_modified(&nested, host.now() + Duration::from_secs(120));
+    assert!(changed_since(host.as_ref(), JKSV, Some(last_sync)));
+}
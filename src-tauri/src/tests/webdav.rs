@@ -0,0 +1,161 @@
+use crate::webdav::{parse_multistatus, WebDavClient};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+const FOLDER: &str = "/JKSV/Hollow%20Knight/";
+// knight:hornet
+const CREDENTIALS: &str = "Basic a25pZ2h0Omhvcm5ldA==";
+
+type Files = Arc<Mutex<BTreeMap<String, (Vec<u8>, Option<String>)>>>;
+
+fn file_response(href: &str, modified: Option<&str>, size: usize) -> String {
+    let modified = modified.map(|modified| format!("<d:getlastmodified>{}</d:getlastmodified>", modified)).unwrap_or_default();
+    format!(
+        "<d:response><d:href>{}</d:href><d:propstat><d:prop>{}<d:getcontentlength>{}</d:getcontentlength><d:resourcetype/></d:prop></d:propstat></d:response>",
+        href, modified, size
+    )
+}
+
+fn folder_response(href: &str) -> String {
+    format!("<d:response><d:href>{}</d:href><d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat></d:response>", href)
+}
+
+// a password protected share on loopback that keeps the files of FOLDER in memory and
+// answers one request per connection
+struct Share {
+    url: String,
+    files: Files,
+}
+
+impl Share {
+    fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/JKSV/Hollow Knight", listener.local_addr().unwrap());
+        let files = Files::default();
+        let served = files.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                Self::serve(stream, &served);
+            }
+        });
+        Self { url, files }
+    }
+
+    fn add(&self, name: &str, data: &[u8], modified: Option<&str>) {
+        self.files.lock().unwrap().insert(name.to_string(), (data.to_vec(), modified.map(str::to_string)));
+    }
+
+    fn client(&self, password: &str) -> WebDavClient {
+        WebDavClient::new(&self.url, Some("knight".to_string()), Some(password.to_string()))
+    }
+
+    fn serve(mut stream: TcpStream, files: &Files) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let mut parts = request_line.split_whitespace();
+        let (method, path) = (parts.next().unwrap_or_default().to_string(), parts.next().unwrap_or_default().to_string());
+
+        let (mut length, mut authorization) = (0, None);
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let Some((name, value)) = line.trim_end().split_once(':') else { break };
+            match name.to_ascii_lowercase().as_str() {
+                "content-length" => length = value.trim().parse().unwrap(),
+                "authorization" => authorization = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+
+        let mut files = files.lock().unwrap();
+        let name = path.strip_prefix(FOLDER).unwrap_or(&path).to_string();
+        let (status, reply) = match method.as_str() {
+            _ if authorization.as_deref() != Some(CREDENTIALS) => ("401 Unauthorized", Vec::new()),
+            "PROPFIND" => {
+                let mut xml = String::from(r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">"#);
+                xml += &folder_response(FOLDER);
+                xml += &folder_response(&format!("{}older/", FOLDER));
+                for (name, (data, modified)) in files.iter() {
+                    xml += &file_response(&format!("{}{}", FOLDER, name), modified.as_deref(), data.len());
+                }
+                ("207 Multi-Status", (xml + "</d:multistatus>").into_bytes())
+            }
+            "GET" => match files.get(&name) {
+                Some((data, _)) => ("200 OK", data.clone()),
+                None => ("404 Not Found", Vec::new()),
+            },
+            "PUT" => {
+                files.insert(name, (body, Some(chrono::Utc::now().to_rfc2822())));
+                ("201 Created", Vec::new())
+            }
+            _ => ("405 Method Not Allowed", Vec::new()),
+        };
+        let _ = write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, reply.len());
+        let _ = stream.write_all(&reply);
+    }
+}
+
+#[test]
+fn multistatus_keeps_files_whatever_the_namespace_prefix() {
+    let xml = r#"<?xml version="1.0"?>
+        <D:multistatus xmlns:D="DAV:">
+          <D:response><D:href>/dav/</D:href><D:propstat><D:prop><D:resourcetype><D:collection/></D:resourcetype></D:prop></D:propstat></D:response>
+          <D:response>
+            <D:href>/dav/Hornet%20-%202024.06.01.zip</D:href>
+            <D:propstat><D:prop>
+              <D:getlastmodified>Sat, 01 Jun 2024 12:00:00 GMT</D:getlastmodified>
+              <D:getcontentlength>2048</D:getcontentlength>
+            </D:prop></D:propstat>
+          </D:response>
+          <response xmlns="DAV:"><href>/dav/notes.txt</href><propstat><prop><getcontentlength>oops</getcontentlength></prop></propstat></response>
+        </D:multistatus>"#;
+
+    let entries = parse_multistatus(xml).unwrap();
+    let found: Vec<(&str, Option<String>, Option<u64>)> = entries
+        .iter()
+        .map(|entry| (entry.name.as_str(), entry.modified.map(|time| time.to_rfc3339()), entry.size))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("Hornet - 2024.06.01.zip", Some("2024-06-01T12:00:00+00:00".to_string()), Some(2048)),
+            ("notes.txt", None, None),
+        ]
+    );
+    assert_eq!(entries[0].url, "/dav/Hornet%20-%202024.06.01.zip");
+}
+
+#[test]
+fn backups_are_listed_newest_first_with_undated_ones_last() {
+    let share = Share::start();
+    share.add("undated.zip", b"u", None);
+    share.add("Hornet%20-%202024.06.01.zip", b"june", Some("Sat, 01 Jun 2024 12:00:00 GMT"));
+    share.add("Hornet%20-%202024.08.01.zip", b"august", Some("Thu, 01 Aug 2024 12:00:00 GMT"));
+    share.add("Hornet%20-%202024.07.01.zip", b"july", Some("Mon, 01 Jul 2024 12:00:00 GMT"));
+    share.add("readme.txt", b"not a backup", Some("Sun, 01 Sep 2024 12:00:00 GMT"));
+
+    let backups = share.client("hornet").list_backups().unwrap();
+    let names: Vec<&str> = backups.iter().map(|backup| backup.name.as_str()).collect();
+    assert_eq!(names, vec!["Hornet - 2024.08.01.zip", "Hornet - 2024.07.01.zip", "Hornet - 2024.06.01.zip", "undated.zip"]);
+    assert_eq!(backups[0].url, format!("{}/Hornet%20-%202024.08.01.zip", share.url.replace(' ', "%20")));
+}
+
+#[test]
+fn uploads_download_back_byte_for_byte() {
+    let share = Share::start();
+    share.add("Hornet%20-%202024.06.01.zip", b"older", Some("Sat, 01 Jun 2024 12:00:00 GMT"));
+    let client = share.client("hornet");
+
+    client.upload("Hornet - 2025.01.01.zip", b"PK\x03\x04 backup").unwrap();
+    let newest = client.newest_backup().unwrap().expect("a backup");
+    assert_eq!(newest.name, "Hornet - 2025.01.01.zip");
+    assert_eq!(newest.size, Some(11));
+    assert_eq!(client.download(&newest).unwrap(), b"PK\x03\x04 backup");
+
+    assert!(share.client("wrong").list_backups().is_err());
+}
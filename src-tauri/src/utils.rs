@@ -1,10 +1,14 @@
-use std::path::PathBuf;
+use crate::host::Host;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub struct GameConfig {
     pub name: String,
     pub display_name: String,
     pub path: String,
+    pub steam_app_id: u32,
+    pub executables: Vec<String>,
+    pub jksv_folder: String,
 }
 
 pub struct Games;
@@ -16,18 +20,32 @@ impl Games {
                 name: "Hollow Knight".to_string(),
                 display_name: "Hollow Knight".to_string(),
                 path: "Hollow Knight".to_string(),
+                steam_app_id: 367520,
+                executables: vec![
+                    "hollow_knight.exe".to_string(),
+                    "hollow_knight.x86_64".to_string(),
+                    "Hollow Knight".to_string(),
+                ],
+                jksv_folder: "Hollow Knight".to_string(),
             }),
             "silksong" => Some(GameConfig {
                 name: "Silksong".to_string(),
                 display_name: "Silksong".to_string(),
                 path: "Hollow Knight Silksong".to_string(),
+                steam_app_id: 1030300,
+                executables: vec![
+                    "Hollow Knight Silksong.exe".to_string(),
+                    "Hollow Knight Silksong.x86_64".to_string(),
+                    "Hollow Knight Silksong".to_string(),
+                ],
+                jksv_folder: "Hollow Knight  Silksong".to_string(),
             }),
             _ => None,
         }
     }
 }
 
-pub fn get_game_paths(game_key: Option<&str>) -> Vec<(String, PathBuf)> {
+pub fn get_game_paths(host: &dyn Host, game_key: Option<&str>) -> Vec<(String, PathBuf)> {
     let mut all_paths = Vec::new();
     let games_to_check: Vec<&str> = match game_key {
         Some(key) => vec![key],
@@ -38,7 +56,7 @@ pub fn get_game_paths(game_key: Option<&str>) -> Vec<(String, PathBuf)> {
         if let Some(game_config) = Games::get_config(game) {
             let mut game_paths = Vec::new();
 
-            if let Some(home_dir) = dirs::home_dir() {
+            if let Some(home_dir) = host.home_dir() {
                 let path = home_dir
                     .join("AppData")
                     .join("LocalLow")
@@ -47,7 +65,7 @@ pub fn get_game_paths(game_key: Option<&str>) -> Vec<(String, PathBuf)> {
                 game_paths.push(path);
             }
 
-            if let Ok(username) = std::env::var("USERNAME") {
+            if let Some(username) = host.var("USERNAME") {
                 let path = PathBuf::from("C:")
                     .join("Users")
                     .join(username)
@@ -71,8 +89,8 @@ pub fn get_game_paths(game_key: Option<&str>) -> Vec<(String, PathBuf)> {
     all_paths
 }
 
-pub fn is_jksv_format(file_path: &str) -> bool {
-    let path = std::path::Path::new(file_path);
+pub fn is_jksv_format(host: &dyn Host, file_path: &str) -> bool {
+    let path = Path::new(file_path);
     
     // check for zip file (legacy support)
     if let Some(ext) = path.extension() {
@@ -82,5 +100,5 @@ pub fn is_jksv_format(file_path: &str) -> bool {
     }
     
     // check for directory or file without extension
-    path.is_dir() || (!path.exists() && !file_path.contains('.'))
+    host.is_dir(path) || (!host.exists(path) && !file_path.contains('.'))
 }
\ No newline at end of file
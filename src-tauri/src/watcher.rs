@@ -0,0 +1,187 @@
+use crate::host::Host;
+use crate::save_manager::SaveManager;
+use crate::sync_config;
+use crate::utils::is_jksv_format;
+use chrono::{DateTime, Utc};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+use ts_rs::TS;
+
+pub const WATCHER_EVENT: &str = "sync-watcher";
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct WatcherEvent {
+    pub game: String,
+    pub profile: String,
+    pub kind: String,
+    pub message: String,
+}
+
+// keyed by sync_config::profile_key, so the default profile's key is still the game name
+#[derive(Default)]
+pub struct WatcherRegistry {
+    watchers: Mutex<HashMap<String, RecommendedWatcher>>,
+}
+
+impl WatcherRegistry {
+    pub fn watched_games(&self) -> Vec<String> {
+        self.watchers
+            .lock()
+            .map(|watchers| watchers.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub async fn start(&self, app: tauri::AppHandle, game: &str, profile: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let manager = app.state::<SaveManager>();
+        let config = manager.load_config(game, profile).await?;
+        if !config.is_configured() {
+            return Err("Save sync not configured".into());
+        }
+
+        let host = manager.host();
+        let targets = [watch_target(host.as_ref(), &config.pc_save, false), watch_target(host.as_ref(), &config.switch_save, true)];
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                if !event.kind.is_access() {
+                    let _ = tx.send(());
+                }
+            }
+        })?;
+
+        let mut watched = Vec::new();
+        for target in targets.iter().flatten() {
+            watcher.watch(&target.0, target.1)?;
+            watched.push(target.0.to_string_lossy().to_string());
+        }
+
+        if watched.is_empty() {
+            return Err("Neither configured save location exists yet".into());
+        }
+
+        self.watchers
+            .lock()
+            .map_err(|_| "Watcher registry poisoned")?
+            .insert(sync_config::profile_key(game, profile), watcher);
+
+        let (game, profile) = (game.to_string(), profile.to_string());
+        std::thread::spawn(move || debounce_loop(app, game, profile, rx));
+
+        Ok(watched)
+    }
+
+    pub fn stop(&self, game: &str, profile: &str) -> bool {
+        // dropping the watcher closes the channel, which ends the debounce thread
+        self.watchers
+            .lock()
+            .map(|mut watchers| watchers.remove(&sync_config::profile_key(game, profile)).is_some())
+            .unwrap_or(false)
+    }
+}
+
+// watch the save's folder rather than the file so atomic replaces are still seen;
+// a jksv backup folder (e.g. on a mounted sd card) is watched recursively
+pub fn watch_target(host: &dyn Host, path: &str, switch_side: bool) -> Option<(PathBuf, RecursiveMode)> {
+    let path = Path::new(path);
+    if switch_side && is_jksv_format(host, &path.to_string_lossy()) && host.is_dir(path) {
+        return Some((path.to_path_buf(), RecursiveMode::Recursive));
+    }
+    path.parent()
+        .filter(|parent| host.is_dir(parent))
+        .map(|parent| (parent.to_path_buf(), RecursiveMode::NonRecursive))
+}
+
+fn debounce_loop(app: tauri::AppHandle, game: String, profile: String, rx: mpsc::Receiver<()>) {
+    emit(&app, &game, &profile, "started", "Watching save locations for changes".to_string());
+
+    'events: while rx.recv().is_ok() {
+        // wait for the writes to settle before reading anything
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => break 'events,
+            }
+        }
+
+        tauri::async_runtime::block_on(handle_change(&app, &game, &profile));
+    }
+
+    emit(&app, &game, &profile, "stopped", "Watcher stopped".to_string());
+}
+
+async fn handle_change(app: &tauri::AppHandle, game: &str, profile: &str) {
+    let manager = app.state::<SaveManager>();
+    let config = match manager.load_config(game, profile).await {
+        Ok(config) => config,
+        Err(e) => {
+            emit(app, game, profile, "error", e.to_string());
+            return;
+        }
+    };
+
+    let host = manager.host();
+    let pc_changed = changed_since(host.as_ref(), &config.pc_save, config.last_sync);
+    let switch_changed = changed_since(host.as_ref(), &config.switch_save, config.last_sync);
+
+    // our own writes land before lastSync is stamped, so they never count as changes
+    let direction = match (pc_changed, switch_changed) {
+        (false, false) => return,
+        (true, true) => {
+            emit(app, game, profile, "conflict", "Both PC and Switch saves changed since the last sync".to_string());
+            return;
+        }
+        (true, false) => "pc-to-switch",
+        (false, true) => "switch-to-pc",
+    };
+
+    emit(app, game, profile, "change", format!("Detected change, syncing {}", direction));
+    match manager.sync(game, profile, Some(direction.to_string()), false).await {
+        Ok(result) => emit(app, game, profile, "synced", [vec![result.message], result.warnings].concat().join(". ")),
+        Err(e) => emit(app, game, profile, "error", e.to_string()),
+    }
+}
+
+pub fn changed_since(host: &dyn Host, path: &str, last_sync: Option<DateTime<Utc>>) -> bool {
+    let Some(modified) = latest_modified(host, Path::new(path)) else {
+        return false;
+    };
+    match last_sync {
+        Some(last_sync) => DateTime::<Utc>::from(modified) > last_sync,
+        None => true,
+    }
+}
+
+// newest mtime of a file, or of anything inside a jksv backup folder
+fn latest_modified(host: &dyn Host, path: &Path) -> Option<SystemTime> {
+    let metadata = host.metadata(path).ok()?;
+    if !metadata.is_dir {
+        return Some(metadata.modified);
+    }
+
+    let mut latest = Some(metadata.modified);
+    for entry in host.read_dir(path).ok()? {
+        if let Some(modified) = latest_modified(host, &entry) {
+            latest = Some(latest.map_or(modified, |current| current.max(modified)));
+        }
+    }
+    latest
+}
+
+fn emit(app: &tauri::AppHandle, game: &str, profile: &str, kind: &str, message: String) {
+    let _ = app.emit_all(WATCHER_EVENT, WatcherEvent {
+        game: game.to_string(),
+        profile: profile.to_string(),
+        kind: kind.to_string(),
+        message,
+    });
+}
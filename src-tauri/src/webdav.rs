@@ -0,0 +1,164 @@
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::io::Read;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export)]
+pub struct RemoteBackup {
+    pub name: String,
+    pub url: String,
+    pub modified: Option<DateTime<Utc>>,
+    #[ts(type = "number | null")]
+    pub size: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct WebDavClient {
+    base_url: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+pub fn is_webdav_url(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+impl WebDavClient {
+    pub fn new(base_url: &str, username: Option<String>, password: Option<String>) -> Self {
+        let mut base_url = base_url.replace(' ', "%20");
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Self { base_url, username, password }
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let request = ureq::request(method, url);
+        match &self.username {
+            Some(username) => {
+                let credentials = format!("{}:{}", username, self.password.as_deref().unwrap_or(""));
+                request.set("Authorization", &format!("Basic {}", general_purpose::STANDARD.encode(credentials)))
+            }
+            None => request,
+        }
+    }
+
+    // jksv uploads one zip per backup into the title's folder
+    pub fn list_backups(&self) -> Result<Vec<RemoteBackup>, Box<dyn std::error::Error>> {
+        let body = r#"<?xml version="1.0" encoding="utf-8"?><d:propfind xmlns:d="DAV:"><d:prop><d:getlastmodified/><d:getcontentlength/><d:resourcetype/></d:prop></d:propfind>"#;
+        let response = self
+            .request("PROPFIND", &self.base_url)
+            .set("Depth", "1")
+            .set("Content-Type", "application/xml")
+            .send_string(body)?;
+        let xml = response.into_string()?;
+
+        let mut backups = parse_multistatus(&xml)?
+            .into_iter()
+            .filter(|backup| backup.name.to_lowercase().ends_with(".zip"))
+            .map(|mut backup| {
+                backup.url = self.file_url(&backup.name);
+                backup
+            })
+            .collect::<Vec<_>>();
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.modified));
+        Ok(backups)
+    }
+
+    pub fn newest_backup(&self) -> Result<Option<RemoteBackup>, Box<dyn std::error::Error>> {
+        Ok(self.list_backups()?.into_iter().next())
+    }
+
+    pub fn download(&self, backup: &RemoteBackup) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let response = self.request("GET", &backup.url).call()?;
+        let mut data = Vec::new();
+        response.into_reader().read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    pub fn upload(&self, name: &str, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        self.request("PUT", &self.file_url(name))
+            .set("Content-Type", "application/zip")
+            .send_bytes(data)?;
+        Ok(())
+    }
+
+    fn file_url(&self, name: &str) -> String {
+        format!("{}{}", self.base_url, encode_path_segment(name))
+    }
+}
+
+fn encode_path_segment(segment: &str) -> String {
+    let mut encoded = String::new();
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn decode_path_segment(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+// pulls file entries out of a PROPFIND multistatus body, whatever namespace prefix the server uses
+pub fn parse_multistatus(xml: &str) -> Result<Vec<RemoteBackup>, Box<dyn std::error::Error>> {
+    let response_regex = regex::Regex::new(r"(?is)<(?:\w+:)?response\b[^>]*>(.*?)</(?:\w+:)?response>")?;
+    let href_regex = regex::Regex::new(r"(?is)<(?:\w+:)?href\b[^>]*>(.*?)</(?:\w+:)?href>")?;
+    let modified_regex = regex::Regex::new(r"(?is)<(?:\w+:)?getlastmodified\b[^>]*>(.*?)</(?:\w+:)?getlastmodified>")?;
+    let length_regex = regex::Regex::new(r"(?is)<(?:\w+:)?getcontentlength\b[^>]*>(.*?)</(?:\w+:)?getcontentlength>")?;
+    let collection_regex = regex::Regex::new(r"(?is)<(?:\w+:)?collection\b")?;
+
+    let mut entries = Vec::new();
+    for response in response_regex.captures_iter(xml) {
+        let block = &response[1];
+        if collection_regex.is_match(block) {
+            continue;
+        }
+        let Some(href) = href_regex.captures(block).map(|c| c[1].trim().to_string()) else {
+            continue;
+        };
+        let Some(name) = href.trim_end_matches('/').rsplit('/').next().map(decode_path_segment) else {
+            continue;
+        };
+        if name.is_empty() {
+            continue;
+        }
+
+        let modified = modified_regex
+            .captures(block)
+            .and_then(|c| DateTime::parse_from_rfc2822(c[1].trim()).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        let size = length_regex.captures(block).and_then(|c| c[1].trim().parse::<u64>().ok());
+
+        entries.push(RemoteBackup {
+            name,
+            url: href,
+            modified,
+            size,
+        });
+    }
+
+    Ok(entries)
+}
@@ -1,5 +1,6 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
 import { HollowButton } from './components/HollowButton';
 import { SaveCard } from './components/SaveCard';
 import { HollowPanel } from './components/HollowPanel';
@@ -9,6 +10,16 @@ import { ToastContainer } from './components/Toast';
 import { GameToggle } from './components/GameToggle';
 import { TabSlider } from './components/TabSlider';
 import { SwitchTutorial } from './components/SwitchTutorial';
+import { ProfileBar } from './components/ProfileBar';
+import { SyncPlanDialog } from './components/SyncPlanDialog';
+import { OperationProgress } from './components/OperationProgress';
+import type { ConvertResult } from './bindings/ConvertResult';
+import type { DetectedSave } from './bindings/DetectedSave';
+import type { ProgressEvent } from './bindings/ProgressEvent';
+import type { SyncConfig } from './bindings/SyncConfig';
+import type { SyncResult } from './bindings/SyncResult';
+import type { SyncPlan } from './bindings/SyncPlan';
+import type { WatcherEvent } from './bindings/WatcherEvent';
 import { 
   RefreshCw, 
   Settings, 
@@ -19,33 +30,35 @@ import {
   Monitor,
   Zap,
   FolderOpen,
-  Plus
+  Plus,
+  Undo2
 } from 'lucide-react';
 
-interface SaveData {
-  slot: number;
-  file: string;
-  path: string;
-  modified: string;
-  size: number;
-  gameDisplayName: string;
-  game: string;
-  exists?: boolean;
-}
+type ConvertDirection = 'auto' | 'pc-to-switch' | 'switch-to-pc' | 'to-json' | 'json-to-pc' | 'json-to-switch';
 
-interface Config {
-  pcSave: string;
-  switchSave: string;
-  lastSync: string | null;
-}
+// input/output descriptions and file extensions per conversion direction
+const CONVERT_FORMATS: Record<ConvertDirection, { input: string; output: string; inputExt: string[]; outputExt: string }> = {
+  'auto': { input: 'Any save', output: 'Converted', inputExt: ['dat', 'json', 'zip'], outputExt: 'dat' },
+  'pc-to-switch': { input: 'PC', output: 'Switch', inputExt: ['dat'], outputExt: 'dat' },
+  'switch-to-pc': { input: 'Switch', output: 'PC', inputExt: ['dat'], outputExt: 'dat' },
+  'to-json': { input: 'PC or Switch', output: 'JSON', inputExt: ['dat'], outputExt: 'json' },
+  'json-to-pc': { input: 'JSON', output: 'PC', inputExt: ['json'], outputExt: 'dat' },
+  'json-to-switch': { input: 'JSON', output: 'Switch', inputExt: ['json'], outputExt: 'dat' }
+};
 
 function App() {
-  const [saves, setSaves] = useState<SaveData[]>([]);
+  const [saves, setSaves] = useState<DetectedSave[]>([]);
   const [selectedGame, setSelectedGame] = useState<string>('silksong');
-  const [config, setConfig] = useState<Config | null>(null);
+  const [profiles, setProfiles] = useState<string[]>(['default']);
+  const [selectedProfile, setSelectedProfile] = useState('default');
+  const [config, setConfig] = useState<SyncConfig | null>(null);
   const [loading, setLoading] = useState(false);
   const [syncing, setSyncing] = useState(false);
   const [syncDirection, setSyncDirection] = useState<string | null>(null);
+  const [undoNeedsForce, setUndoNeedsForce] = useState(false);
+  const [pendingSync, setPendingSync] = useState<SyncPlan | null>(null);
+  const [progress, setProgress] = useState<ProgressEvent | null>(null);
+  const [watching, setWatching] = useState(false);
   const [currentTab, setCurrentTab] = useState<'sync' | 'setup' | 'convert'>('sync');
   const [showManualSetup, setShowManualSetup] = useState(false);
   const [manualPcPath, setManualPcPath] = useState('');
@@ -57,15 +70,60 @@ function App() {
   }>>([]);
   const [convertInputPath, setConvertInputPath] = useState('');
   const [convertOutputPath, setConvertOutputPath] = useState('');
-  const [convertDirection, setConvertDirection] = useState<'pc-to-switch' | 'switch-to-pc'>('pc-to-switch');
+  const [convertDirection, setConvertDirection] = useState<ConvertDirection>('auto');
+  const [convertMinify, setConvertMinify] = useState(false);
   const [converting, setConverting] = useState(false);
   const [showTutorial, setShowTutorial] = useState(false);
   const [isTutorialHidden, setHideTutorial] = useState(false);
 
   useEffect(() => {
-    loadInitialData();
+    setSelectedProfile('default');
   }, [selectedGame]);
 
+  useEffect(() => {
+    loadInitialData();
+  }, [selectedGame, selectedProfile]);
+
+  // watchers are keyed like sync_config::profile_key, the default profile by the bare game name
+  const watcherKey = selectedProfile === 'default' ? selectedGame : `${selectedGame}/${selectedProfile}`;
+
+  useEffect(() => {
+    invoke<string[]>('get_watched_games')
+      .then((watched) => setWatching(watched.includes(watcherKey)))
+      .catch(() => setWatching(false));
+  }, [watcherKey]);
+
+  // the watcher syncs in the background; tell the user what it did and pick up the new lastSync
+  useEffect(() => {
+    const unlisten = listen<WatcherEvent>('sync-watcher', (event) => {
+      const { game, profile, kind, message } = event.payload;
+      const label = profile === 'default' ? game : `${game} (${profile})`;
+      if (kind === 'synced') {
+        showToast('success', `Auto-sync ${label}: ${message}`);
+      } else if (kind === 'error') {
+        showToast('error', `Auto-sync ${label} failed: ${message}`);
+      } else {
+        showToast('info', `Auto-sync ${label}: ${message}`);
+      }
+      if (game === selectedGame && profile === selectedProfile && kind === 'synced') {
+        loadConfig();
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [selectedGame, selectedProfile]);
+
+  // scans and syncs report progress as events, tagged with the operation id passed to the command
+  useEffect(() => {
+    const unlisten = listen<ProgressEvent>('operation-progress', (event) => {
+      setProgress(event.payload.phase === 'done' ? null : event.payload);
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   useEffect(() => {
     const disableContextMenu = (e: MouseEvent) => {
       e.preventDefault();
@@ -99,10 +157,15 @@ function App() {
     try {
       await Promise.all([
         loadSaves(),
-        loadConfig()
+        loadConfig(),
+        loadProfiles()
       ]);
     } catch (error) {
-      showToast('error', `Failed to load data: ${error}`);
+      if (isCancelled(error)) {
+        showToast('info', 'Save scan cancelled');
+      } else {
+        showToast('error', `Failed to load data: ${error}`);
+      }
     } finally {
       setLoading(false);
     }
@@ -110,22 +173,77 @@ function App() {
 
   const loadSaves = async () => {
     try {
-      const result = await invoke<SaveData[]>('detect_saves', { gameFilter: selectedGame });
+      const result = await invoke<DetectedSave[]>('detect_saves', { gameFilter: selectedGame });
       setSaves(result);
     } catch (error) {
       throw new Error(`Failed to detect saves: ${error}`);
+    } finally {
+      setProgress(null);
+    }
+  };
+
+  const isCancelled = (error: unknown) => String(error).includes('Operation cancelled');
+
+  const cancelOperation = async () => {
+    if (progress) {
+      await invoke<boolean>('cancel_operation', { operationId: progress.operationId });
+    }
+  };
+
+  const toggleWatcher = async (enabled: boolean) => {
+    try {
+      if (enabled) {
+        const paths = await invoke<string[]>('start_watcher', { game: selectedGame, profile: selectedProfile });
+        showToast('info', `Watching ${paths.length} location(s), saves sync automatically when they change`);
+      } else {
+        await invoke<boolean>('stop_watcher', { game: selectedGame, profile: selectedProfile });
+      }
+      setWatching(enabled);
+    } catch (error) {
+      showToast('error', `Could not ${enabled ? 'start' : 'stop'} auto-sync: ${error}`);
     }
   };
 
   const loadConfig = async () => {
     try {
-      const result = await invoke<Config>('load_config', { game: selectedGame });
+      const result = await invoke<SyncConfig>('load_config', { game: selectedGame, profile: selectedProfile });
+      if (result.quarantined) {
+        showToast('warning', `Sync settings were unreadable and have been reset. The old file was kept at ${result.quarantined}`);
+      }
       setConfig(result);
     } catch (error) {
       throw new Error(`Failed to load config: ${error}`);
     }
   };
 
+  const loadProfiles = async () => {
+    const result = await invoke<string[]>('list_profiles', { game: selectedGame });
+    setProfiles(result);
+  };
+
+  const runProfileAction = async (action: () => Promise<unknown>, nextProfile: string, message: string) => {
+    try {
+      await action();
+      await loadProfiles();
+      setSelectedProfile(nextProfile);
+      showToast('success', message);
+    } catch (error) {
+      showToast('error', `${error}`);
+    }
+  };
+
+  const handleCreateProfile = (name: string) =>
+    runProfileAction(() => invoke('create_profile', { game: selectedGame, profile: name }), name, `Created profile ${name}`);
+
+  const handleCloneProfile = (name: string) =>
+    runProfileAction(() => invoke('clone_profile', { game: selectedGame, from: selectedProfile, to: name }), name, `Cloned ${selectedProfile} to ${name}`);
+
+  const handleRenameProfile = (name: string) =>
+    runProfileAction(() => invoke('rename_profile', { game: selectedGame, from: selectedProfile, to: name }), name, `Renamed ${selectedProfile} to ${name}`);
+
+  const handleDeleteProfile = () =>
+    runProfileAction(() => invoke('delete_profile', { game: selectedGame, profile: selectedProfile }), 'default', `Deleted profile ${selectedProfile}`);
+
   const showToast = (type: 'success' | 'error' | 'warning' | 'info', message: string) => {
     const id = Date.now().toString() + Math.random().toString(36);
     setToasts(prev => [...prev, { id, type, message }]);
@@ -168,19 +286,38 @@ function App() {
     }
   };
 
+  // shows what the sync would do first; nothing is written until the plan is confirmed, and
+  // confirming runs the planned direction so an auto sync can't flip between plan and run
   const handleSync = async (forceDirection?: string) => {
     if (!config || !config.pcSave || !config.switchSave) {
       showToast('warning', 'Please configure save paths first');
       return;
     }
 
+    try {
+      const plan = await invoke<SyncPlan>('plan_sync', {
+        game: selectedGame,
+        profile: selectedProfile,
+        forceDirection
+      });
+      setPendingSync(plan);
+    } catch (error) {
+      showToast('error', `Sync failed: ${error}`);
+    }
+  };
+
+  const runSync = async (forceDirection: string | undefined, force: boolean) => {
+    if (!config) return;
+    setPendingSync(null);
     setSyncing(true);
     setSyncDirection(forceDirection || null);
     
     try {
-      await invoke<string>('sync_saves', { 
+      const result = await invoke<SyncResult>('sync_saves', { 
         game: selectedGame, 
-        forceDirection 
+        profile: selectedProfile,
+        forceDirection,
+        force
       });
       
       let message = 'Save files synchronized successfully';
@@ -197,21 +334,58 @@ function App() {
         pathToOpen = config.pcSave;
       }
       
+      setUndoNeedsForce(false);
       showToast('success', message);
+      for (const warning of result.warnings) {
+        showToast('warning', warning);
+      }
       await loadConfig();
       
       if (forceDirection === 'pc-to-switch' && pathToOpen) {
         setTimeout(() => openPath(pathToOpen, 'destination'), 500);
       }
     } catch (error) {
-      showToast('error', `Sync failed: ${error}`);
+      if (isCancelled(error)) {
+        // cancels only land before the destination is overwritten, and the undo copy of the
+        // previous sync is kept until a write succeeds
+        showToast('info', 'Sync cancelled before either save was changed');
+      } else {
+        showToast('error', `Sync failed: ${error}`);
+      }
     } finally {
       setSyncing(false);
       setSyncDirection(null);
+      setProgress(null);
+    }
+  };
+
+  // the backend refuses when the destination changed after the sync; a second click forces it
+  const handleUndoSync = async () => {
+    setSyncing(true);
+    try {
+      const message = await invoke<string>('undo_last_sync', {
+        game: selectedGame,
+        profile: selectedProfile,
+        force: undoNeedsForce
+      });
+      setUndoNeedsForce(false);
+      showToast('success', message);
+      await loadConfig();
+    } catch (error) {
+      const message = String(error);
+      if (!undoNeedsForce && message.includes('Force the undo')) {
+        setUndoNeedsForce(true);
+        showToast('warning', `${message} Click Undo again to force it.`);
+      } else {
+        setUndoNeedsForce(false);
+        showToast('error', `Undo failed: ${message}`);
+      }
+    } finally {
+      setSyncing(false);
     }
   };
 
-  const handleAutoSetup = async (save: SaveData) => {
+  const handleAutoSetup = async (save: DetectedSave) => {
     setLoading(true);
     try {
       const savesDir = await invoke<string>('get_saves_dir').catch(() => 'saves');
@@ -219,12 +393,13 @@ function App() {
       const fullSwitchPath = `${savesDir}\\${outputName}`;
       
       const newConfig = {
+        ...config,
         pcSave: save.path,
         switchSave: fullSwitchPath,
         lastSync: null
       };
       
-      await invoke('save_config', { game: selectedGame, config: newConfig });
+      await invoke('save_config', { game: selectedGame, profile: selectedProfile, config: newConfig });
       await loadConfig();
       showToast('success', `Configured sync for ${save.gameDisplayName} slot ${save.slot}`);
     } catch (error) {
@@ -243,12 +418,13 @@ function App() {
     setLoading(true);
     try {
       const newConfig = {
+        ...config,
         pcSave: manualPcPath,
         switchSave: manualSwitchPath,
         lastSync: null
       };
       
-      await invoke('save_config', { game: selectedGame, config: newConfig });
+      await invoke('save_config', { game: selectedGame, profile: selectedProfile, config: newConfig });
       await loadConfig();
       setShowManualSetup(false);
       setManualPcPath('');
@@ -314,19 +490,20 @@ function App() {
 
     setConverting(true);
     try {
-      const result = await invoke<string>('convert_save', {
+      const result = await invoke<ConvertResult>('convert_save', {
         inputPath: convertInputPath,
         outputPath: convertOutputPath || undefined,
-        direction: convertDirection
+        direction: convertDirection === 'auto' ? undefined : convertDirection,
+        minify: convertMinify
       });
       
-      showToast('success', `Conversion completed: ${result}`);
+      showToast('success', `Converted ${result.detected.format} (${result.direction}): ${result.output}`);
       
-      if (convertDirection === 'pc-to-switch' && !isTutorialHidden) {
+      if (result.direction === 'pc-to-switch' && !isTutorialHidden) {
         setShowTutorial(true);
       }
       
-      setTimeout(() => openPath(result, 'converted file'), 500);
+      setTimeout(() => openPath(result.output, 'converted file'), 500);
       
       setConvertInputPath('');
       setConvertOutputPath('');
@@ -337,9 +514,41 @@ function App() {
     }
   };
 
+  // writes a .dat the web save editor opens directly, whatever variant the input was
+  const handleWebEditorExport = async () => {
+    if (!convertInputPath) {
+      showToast('warning', 'Please select an input file');
+      return;
+    }
+
+    setConverting(true);
+    try {
+      const variant = await invoke<string>('detect_save_variant', { path: convertInputPath });
+      const result = await invoke<string>('export_web_editor', {
+        inputPath: convertInputPath,
+        outputPath: convertOutputPath || undefined
+      });
+      showToast('success', `Exported ${variant} save for the web editor: ${result}`);
+      setTimeout(() => openPath(result, 'exported file'), 500);
+    } catch (error) {
+      showToast('error', `Export failed: ${error}`);
+    } finally {
+      setConverting(false);
+    }
+  };
+
   const renderSyncTab = () => (
     <div className="h-full overflow-y-auto">
       <div className="space-y-6 p-1">
+      <ProfileBar
+        profiles={profiles}
+        value={selectedProfile}
+        onChange={setSelectedProfile}
+        onCreate={handleCreateProfile}
+        onClone={handleCloneProfile}
+        onRename={handleRenameProfile}
+        onDelete={handleDeleteProfile}
+      />
       <HollowPanel title="Sync Status">
         {config?.pcSave && config?.switchSave ? (
           <div className="space-y-4">
@@ -388,6 +597,16 @@ function App() {
                 Last sync: {new Date(config.lastSync).toLocaleString()}
               </div>
             )}
+
+            <label className="flex items-center gap-2 text-sm text-knight-300 cursor-pointer">
+              <input
+                type="checkbox"
+                checked={watching}
+                onChange={(e) => toggleWatcher(e.target.checked)}
+                className="accent-silk-500"
+              />
+              Sync automatically when either save changes (while the app is open)
+            </label>
             
             <div className="flex gap-3 justify-center">
               <HollowButton 
@@ -416,7 +635,21 @@ function App() {
               >
                 Switch → PC
               </HollowButton>
+              {config.lastSync && (
+                <HollowButton
+                  icon={Undo2}
+                  variant="secondary"
+                  onClick={handleUndoSync}
+                  disabled={loading || syncing}
+                >
+                  {undoNeedsForce ? 'Undo Anyway' : 'Undo Last Sync'}
+                </HollowButton>
+              )}
             </div>
+
+            {syncing && progress?.operation === 'sync' && (
+              <OperationProgress progress={progress} onCancel={cancelOperation} />
+            )}
           </div>
         ) : (
           <div className="text-center py-8">
@@ -538,6 +771,12 @@ function App() {
               Manual Setup
             </HollowButton>
           </div>
+
+          {progress?.operation === 'detect' && (
+            <div className="mb-3">
+              <OperationProgress progress={progress} onCancel={cancelOperation} />
+            </div>
+          )}
           
           {saves.length > 0 ? (
             <div className="grid grid-cols-2 md:grid-cols-3 lg:grid-cols-4 gap-3 max-h-60 overflow-y-auto">
@@ -590,6 +829,14 @@ function App() {
         <HollowPanel title="Manual Conversion">
           <div className="space-y-2">
         <div className="flex gap-2">
+          <HollowButton
+            variant={convertDirection === 'auto' ? 'primary' : 'secondary'}
+            onClick={() => setConvertDirection('auto')}
+            icon={Zap}
+            size="sm"
+          >
+            Auto
+          </HollowButton>
           <HollowButton
             variant={convertDirection === 'pc-to-switch' ? 'primary' : 'secondary'}
             onClick={() => setConvertDirection('pc-to-switch')}
@@ -607,17 +854,43 @@ function App() {
             Switch → PC
           </HollowButton>
         </div>
+        <div className="flex gap-2">
+          <HollowButton
+            variant={convertDirection === 'to-json' ? 'primary' : 'secondary'}
+            onClick={() => setConvertDirection('to-json')}
+            icon={Download}
+            size="sm"
+          >
+            Export JSON
+          </HollowButton>
+          <HollowButton
+            variant={convertDirection === 'json-to-pc' ? 'primary' : 'secondary'}
+            onClick={() => setConvertDirection('json-to-pc')}
+            icon={Upload}
+            size="sm"
+          >
+            JSON → PC
+          </HollowButton>
+          <HollowButton
+            variant={convertDirection === 'json-to-switch' ? 'primary' : 'secondary'}
+            onClick={() => setConvertDirection('json-to-switch')}
+            icon={Upload}
+            size="sm"
+          >
+            JSON → Switch
+          </HollowButton>
+        </div>
 
         <div>
           <label className="block text-sm font-hollow font-medium text-knight-100 mb-1">
-            Input (.dat)
+            Input (.{CONVERT_FORMATS[convertDirection].inputExt.join(', .')})
           </label>
           <div className="flex gap-2">
             <input
               type="text"
               value={convertInputPath}
               onChange={(e) => setConvertInputPath(e.target.value)}
-              placeholder={`${convertDirection === 'pc-to-switch' ? 'PC' : 'Switch'} file...`}
+              placeholder={`${CONVERT_FORMATS[convertDirection].input} file...`}
               className="flex-1 px-3 py-1.5 bg-void-700 border border-void-600 rounded-md text-knight-100 font-mono text-sm focus:border-silk-500 focus:outline-none"
             />
             <HollowButton
@@ -625,7 +898,7 @@ function App() {
               variant="secondary"
               size="sm"
               onClick={async () => {
-                const extensions = ['dat'];
+                const extensions = CONVERT_FORMATS[convertDirection].inputExt;
                 const path = await openFileDialog('Select Input File', [
                   { name: 'Save File', extensions }
                 ]);
@@ -646,7 +919,7 @@ function App() {
               type="text"
               value={convertOutputPath}
               onChange={(e) => setConvertOutputPath(e.target.value)}
-              placeholder={`${convertDirection === 'pc-to-switch' ? 'Switch' : 'PC'} path...`}
+              placeholder={`${CONVERT_FORMATS[convertDirection].output} path...`}
               className="flex-1 px-3 py-1.5 bg-void-700 border border-void-600 rounded-md text-knight-100 font-mono text-sm focus:border-silk-500 focus:outline-none"
             />
             <HollowButton
@@ -659,7 +932,7 @@ function App() {
                   if (path) setConvertOutputPath(path);
                 } else {
                   const path = await openSaveDialog('Choose Output File', [
-                    { name: 'Save Files', extensions: ['dat'] }
+                    { name: 'Save Files', extensions: [CONVERT_FORMATS[convertDirection].outputExt] }
                   ]);
                   if (path) setConvertOutputPath(path);
                 }
@@ -670,7 +943,19 @@ function App() {
           </div>
         </div>
 
-        <div className="flex justify-center pt-1">
+        {convertDirection === 'to-json' && (
+          <label className="flex items-center gap-2 text-sm text-knight-300 cursor-pointer">
+            <input
+              type="checkbox"
+              checked={convertMinify}
+              onChange={(e) => setConvertMinify(e.target.checked)}
+              className="accent-silk-500"
+            />
+            Minify (one line, smaller file)
+          </label>
+        )}
+
+        <div className="flex justify-center gap-2 pt-1">
           <HollowButton
             onClick={handleConvert}
             disabled={!convertInputPath || converting}
@@ -680,6 +965,15 @@ function App() {
           >
             Convert
           </HollowButton>
+          <HollowButton
+            onClick={handleWebEditorExport}
+            disabled={!convertInputPath || converting}
+            variant="secondary"
+            icon={Download}
+            size="sm"
+          >
+            For Web Editor
+          </HollowButton>
         </div>
           </div>
         </HollowPanel>
@@ -751,6 +1045,14 @@ function App() {
             switchSavePath={config?.switchSave}
           />
         )}
+
+        {pendingSync && (
+          <SyncPlanDialog
+            plan={pendingSync}
+            onConfirm={(force) => runSync(pendingSync.direction, force)}
+            onCancel={() => setPendingSync(null)}
+          />
+        )}
       </div>
     </div>
   );
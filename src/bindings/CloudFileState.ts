@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type CloudFileState = { file: string, accountId: number, syncState: number | null, status: string, sha: string | null, size: number | null, localTime: number | null, remoteTime: number | null, localSha: string | null, matchesLocal: boolean | null, };
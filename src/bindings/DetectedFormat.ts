@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InputFormat } from "./InputFormat";
+import type { SaveVariant } from "./SaveVariant";
+
+export type DetectedFormat = { format: InputFormat, variant: SaveVariant, entry: string | null, };
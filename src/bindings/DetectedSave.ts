@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CloudFileState } from "./CloudFileState";
+import type { RestorePoint } from "./RestorePoint";
+
+export type DetectedSave = { slot: number, file: string, path: string, modified: string, size: number, directory: string, game: string, gameName: string, gameDisplayName: string, basePath: string, steamId: string | null, steamName: string | null, restorePoints: Array<RestorePoint>, cloud: CloudFileState | null, };
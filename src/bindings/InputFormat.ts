@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type InputFormat = "pc" | "pc-no-trailer" | "base64" | "json" | "jksv-folder" | "jksv-zip" | "zip";
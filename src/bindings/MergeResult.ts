@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Finding } from "./Finding";
+
+export type MergeResult = { output: string | null, takenFields: Array<string>, findings: Array<Finding>, written: boolean, };
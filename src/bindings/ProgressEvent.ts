@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ProgressEvent = { operationId: string, operation: string, phase: string, currentFile: string | null, bytesDone: number, bytesTotal: number | null, percent: number | null, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SaveSummary } from "./SaveSummary";
+
+export type RestorePoint = { slot: number, file: string, path: string, kind: string, version: string | null, modified: string, size: number, summary: SaveSummary | null, };
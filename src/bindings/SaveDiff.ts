@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CategoryDiff } from "./CategoryDiff";
+import type { SaveSummary } from "./SaveSummary";
+
+export type SaveDiff = { left: SaveSummary, right: SaveSummary, categories: Array<CategoryDiff>, totalChanges: number, };
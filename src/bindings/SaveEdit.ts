@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SaveEdit = { "kind": "geo", value: number, } | { "kind": "masks", value: number, } | { "kind": "soulVessels", value: number, } | { "kind": "charmOwned", charm: number, owned: boolean, } | { "kind": "toolOwned", tool: string, owned: boolean, } | { "kind": "respawnBench", scene: string, marker: string, } | { "kind": "steelSoul", enabled: boolean, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SaveSummary = { version: string | null, playTime: number | null, completion: number | null, geo: number | null, maxHealth: number | null, respawnScene: string | null, permadeathMode: number | null, };
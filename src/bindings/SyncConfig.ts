@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SyncConfig = { version: number, pcSave: string, switchSave: string, lastSync: string | null, switchSlot: number | null, gitHistory: boolean, webdavUsername: string | null, webdavPassword: string | null, ftpUsername: string | null, ftpPassword: string | null, quarantined?: string, };
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SaveSummary } from "./SaveSummary";
+
+export type SyncLogEntry = { timestamp: string, operation: string, game: string | null, profile: string | null, direction: string | null, source: string | null, destination: string | null, sourceSha: string | null, destinationShaBefore: string | null, destinationShaAfter: string | null, sourceSummary: SaveSummary | null, destinationSummaryBefore: SaveSummary | null, snapshot: string | null, undoCopy: string | null, destinationCreated: boolean, reverts: string | null, reverted?: string, outcome: string, error: string | null, warnings: Array<string>, };
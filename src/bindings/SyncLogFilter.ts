@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SyncLogFilter = { game?: string, profile?: string, operation?: string, outcome?: string, since?: string, until?: string, path?: string, limit?: number, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SyncPlan = { game: string, profile: string, direction: string, reason: string, source: string, destination: string, reads: Array<string>, writes: Array<string>, layout: string, historySnapshot: boolean, undoable: boolean, conflicts: Array<string>, warnings: Array<string>, blockers: Array<string>, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SyncResult = { direction: string, message: string, snapshot: string | null, undoable: boolean, warnings: Array<string>, };
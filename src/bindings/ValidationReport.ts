@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Finding } from "./Finding";
+
+export type ValidationReport = { format: string, decoded: boolean, findings: Array<Finding>, repairs: Array<string>, };
@@ -0,0 +1,45 @@
+import { HollowButton } from './HollowButton';
+import type { ProgressEvent } from '../bindings/ProgressEvent';
+
+interface OperationProgressProps {
+  progress: ProgressEvent;
+  onCancel: () => void;
+}
+
+// the backend only checks for a cancel in these phases; once a sync is keeping its undo copy,
+// writing or committing to history it runs to the end, so Cancel isn't offered
+const CANCELLABLE_PHASES = ['scanning', 'planning', 'extracting'];
+
+const formatBytes = (bytes: number) =>
+  bytes >= 1024 * 1024 ? `${(bytes / (1024 * 1024)).toFixed(1)} MB` : `${Math.ceil(bytes / 1024)} KB`;
+
+export const OperationProgress = ({ progress, onCancel }: OperationProgressProps) => {
+  const percent = progress.percent ?? 0;
+
+  return (
+    <div className="space-y-2 text-sm">
+      <div className="flex items-center justify-between gap-3">
+        <div className="text-knight-300 capitalize">
+          {progress.phase}
+          {progress.bytesTotal !== null && ` • ${formatBytes(progress.bytesDone)} of ${formatBytes(progress.bytesTotal)}`}
+        </div>
+        {CANCELLABLE_PHASES.includes(progress.phase) && (
+          <HollowButton size="sm" variant="secondary" onClick={onCancel}>
+            Cancel
+          </HollowButton>
+        )}
+      </div>
+      <div className="h-2 rounded-full bg-void-700 overflow-hidden">
+        <div
+          className={`h-full bg-silk-500 transition-all ${progress.percent === null ? 'animate-pulse w-full' : ''}`}
+          style={progress.percent === null ? undefined : { width: `${percent}%` }}
+        />
+      </div>
+      {progress.currentFile && (
+        <div className="text-knight-400 font-mono text-xs truncate" title={progress.currentFile}>
+          {progress.currentFile}
+        </div>
+      )}
+    </div>
+  );
+};
@@ -0,0 +1,116 @@
+import React, { useState } from 'react';
+import { Copy, Pencil, Plus, Trash2 } from 'lucide-react';
+import { HollowButton } from './HollowButton';
+
+interface ProfileBarProps {
+  profiles: string[];
+  value: string;
+  onChange: (profile: string) => void;
+  onCreate: (name: string) => Promise<void>;
+  onClone: (name: string) => Promise<void>;
+  onRename: (name: string) => Promise<void>;
+  onDelete: () => Promise<void>;
+}
+
+type PendingAction = 'create' | 'clone' | 'rename' | 'delete' | null;
+
+export const ProfileBar: React.FC<ProfileBarProps> = ({
+  profiles,
+  value,
+  onChange,
+  onCreate,
+  onClone,
+  onRename,
+  onDelete
+}) => {
+  const [pending, setPending] = useState<PendingAction>(null);
+  const [name, setName] = useState('');
+  const isDefault = value === 'default';
+
+  const startAction = (action: PendingAction) => {
+    setPending(action);
+    setName(action === 'rename' ? value : '');
+  };
+
+  const confirmDelete = async () => {
+    await onDelete();
+    setPending(null);
+  };
+
+  const confirmAction = async () => {
+    const trimmed = name.trim();
+    if (!trimmed) return;
+
+    if (pending === 'create') await onCreate(trimmed);
+    if (pending === 'clone') await onClone(trimmed);
+    if (pending === 'rename') await onRename(trimmed);
+    setPending(null);
+  };
+
+  return (
+    <div className="flex flex-wrap items-center gap-2">
+      <span className="text-sm font-hollow text-knight-300">Profile</span>
+      <select
+        value={value}
+        onChange={(e) => onChange(e.target.value)}
+        className="px-3 py-1.5 bg-void-700 border border-void-600 rounded-md text-knight-100 text-sm focus:border-silk-500 focus:outline-none"
+      >
+        {profiles.map((profile) => (
+          <option key={profile} value={profile}>
+            {profile}
+          </option>
+        ))}
+      </select>
+
+      {pending === 'delete' ? (
+        <>
+          <span className="text-sm text-knight-300">
+            Delete profile <span className="text-knight-100">{value}</span>? Its sync settings are removed, its history is kept.
+          </span>
+          <HollowButton size="sm" variant="danger" icon={Trash2} onClick={confirmDelete}>
+            Delete
+          </HollowButton>
+          <HollowButton size="sm" variant="secondary" onClick={() => setPending(null)}>
+            Cancel
+          </HollowButton>
+        </>
+      ) : pending ? (
+        <>
+          <input
+            type="text"
+            value={name}
+            autoFocus
+            onChange={(e) => setName(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter') confirmAction();
+              if (e.key === 'Escape') setPending(null);
+            }}
+            placeholder="Profile name..."
+            className="px-3 py-1.5 bg-void-700 border border-void-600 rounded-md text-knight-100 text-sm focus:border-silk-500 focus:outline-none"
+          />
+          <HollowButton size="sm" onClick={confirmAction} disabled={!name.trim()}>
+            {pending === 'create' ? 'Create' : pending === 'clone' ? 'Clone' : 'Rename'}
+          </HollowButton>
+          <HollowButton size="sm" variant="secondary" onClick={() => setPending(null)}>
+            Cancel
+          </HollowButton>
+        </>
+      ) : (
+        <>
+          <HollowButton size="sm" variant="secondary" icon={Plus} onClick={() => startAction('create')}>
+            New
+          </HollowButton>
+          <HollowButton size="sm" variant="secondary" icon={Copy} onClick={() => startAction('clone')}>
+            Clone
+          </HollowButton>
+          <HollowButton size="sm" variant="secondary" icon={Pencil} disabled={isDefault} onClick={() => startAction('rename')}>
+            Rename
+          </HollowButton>
+          <HollowButton size="sm" variant="secondary" icon={Trash2} disabled={isDefault} onClick={() => startAction('delete')}>
+            Delete
+          </HollowButton>
+        </>
+      )}
+    </div>
+  );
+};
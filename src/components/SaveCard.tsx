@@ -8,6 +8,8 @@ interface SaveData {
   modified: string;
   size: number;
   gameDisplayName: string;
+  steamId?: string | null;
+  steamName?: string | null;
   exists?: boolean;
 }
 
@@ -59,6 +61,12 @@ export const SaveCard: React.FC<SaveCardProps> = ({
       <div className="text-xs text-knight-400 font-mono mt-1 truncate">
         {save.file}
       </div>
+
+      {save.steamId && (
+        <div className="text-xs text-knight-400 mt-1 truncate" title={save.steamId}>
+          {save.steamName ?? `Steam ${save.steamId}`}
+        </div>
+      )}
     </button>
   );
 };
\ No newline at end of file
@@ -0,0 +1,58 @@
+import { HollowButton } from './HollowButton';
+import type { SyncPlan } from '../bindings/SyncPlan';
+
+interface SyncPlanDialogProps {
+  plan: SyncPlan;
+  onConfirm: (force: boolean) => void;
+  onCancel: () => void;
+}
+
+const PathList = ({ label, paths }: { label: string; paths: string[] }) => (
+  <div className="space-y-1">
+    <div className="font-hollow font-semibold text-knight-100">{label}</div>
+    {paths.map((path) => (
+      <div key={path} className="text-knight-300 font-mono text-xs break-all">
+        {path}
+      </div>
+    ))}
+  </div>
+);
+
+export const SyncPlanDialog = ({ plan, onConfirm, onCancel }: SyncPlanDialogProps) => {
+  const blocked = plan.blockers.length > 0;
+  const title = plan.direction === 'pc-to-switch' ? 'PC → Switch' : 'Switch → PC';
+
+  return (
+    <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50 p-4">
+      <div className="bg-void-800 border border-silk-500/30 rounded-lg p-6 max-w-md w-full max-h-[80vh] overflow-y-auto">
+        <h3 className="text-xl font-hollow font-bold text-silk-400 mb-1">Sync {title}</h3>
+        <p className="text-sm text-knight-400 mb-4">Chosen because {plan.reason}</p>
+
+        <div className="space-y-4 text-sm">
+          <PathList label="Reads" paths={plan.reads} />
+          <PathList label="Writes" paths={plan.writes} />
+          <div className="text-knight-300">
+            Layout: {plan.layout}
+            {plan.historySnapshot && ' • snapshot saved to history'}
+            {!plan.undoable && ' • cannot be undone from the app'}
+          </div>
+
+          {[...plan.blockers, ...plan.conflicts, ...plan.warnings].map((message) => (
+            <div key={message} className="p-3 rounded-md border border-yellow-500/40 bg-yellow-500/10 text-yellow-200">
+              {message}
+            </div>
+          ))}
+        </div>
+
+        <div className="flex gap-3 justify-end mt-6">
+          <HollowButton size="sm" variant="secondary" onClick={onCancel}>
+            Cancel
+          </HollowButton>
+          <HollowButton size="sm" onClick={() => onConfirm(blocked)}>
+            {blocked ? 'Sync Anyway' : 'Sync'}
+          </HollowButton>
+        </div>
+      </div>
+    </div>
+  );
+};